@@ -0,0 +1,72 @@
+//! Reusing buffers of `PlanarityTester` against calling `is_planar` for every graph:
+//! `cargo bench --bench planarity`.
+//!
+//! Many small graphs are tested, as in exhaustive tests, where allocations dominate the running time.
+
+use petgraph::graph::NodeIndex;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use spqr_trees::{
+    EdgeLabel, UnGraph,
+    embedding::{PlanarityTester, is_planar, is_planar_bool},
+};
+use std::time::{Duration, Instant};
+
+// random spanning tree with `m - n + 1` random edges added, so sparse graphs are mostly planar
+fn random_connected_graph(n: usize, m: usize, rng: &mut StdRng) -> UnGraph {
+    let mut graph = UnGraph::new_undirected();
+    for v in 0..n {
+        graph.add_node(v as u32);
+    }
+    for v in 1..n {
+        let u = rng.random_range(0..v);
+        graph.add_edge(NodeIndex::new(u), NodeIndex::new(v), EdgeLabel::Real);
+    }
+    for _ in n..=m {
+        let (u, v) = (rng.random_range(0..n), rng.random_range(0..n));
+        if u != v {
+            graph.add_edge(NodeIndex::new(u), NodeIndex::new(v), EdgeLabel::Real);
+        }
+    }
+    graph
+}
+
+// the best of a few runs, so that a single slow run doesn't matter
+fn measure(mut run: impl FnMut() -> usize) -> (Duration, usize) {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            let planar = run();
+            (start.elapsed(), planar)
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    println!(
+        "{:>6} {:>8} {:>14} {:>14} {:>14} {:>14}",
+        "n", "graphs", "is_planar", "test", "is_planar_bool", "test_bool"
+    );
+    for n in [8, 16, 64, 256] {
+        let count = 200_000 / n;
+        let mut rng = StdRng::seed_from_u64(n as u64);
+        let graphs: Vec<UnGraph> = (0..count)
+            .map(|i| random_connected_graph(n, n + i % (2 * n), &mut rng))
+            .collect();
+        let mut tester = PlanarityTester::new();
+
+        let (fresh, expected) = measure(|| graphs.iter().filter(|g| is_planar(g, false).0).count());
+        let (reused, planar) = measure(|| graphs.iter().filter(|g| tester.test(g).0).count());
+        assert_eq!(planar, expected);
+        let (fresh_bool, planar) = measure(|| graphs.iter().filter(|g| is_planar_bool(g)).count());
+        assert_eq!(planar, expected);
+        let (reused_bool, planar) =
+            measure(|| graphs.iter().filter(|g| tester.test_bool(g)).count());
+        assert_eq!(planar, expected);
+
+        println!(
+            "{:>6} {:>8} {:>14.2?} {:>14.2?} {:>14.2?} {:>14.2?}",
+            n, count, fresh, reused, fresh_bool, reused_bool
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Running time of the decomposition of giant bonds: `cargo bench --bench triconnected`.
+//!
+//! A triangle with one side replaced by `k` parallel edges goes through duplicate edge handling
+//! and merging of bonds, the time should grow linearly with `k`.
+
+use spqr_trees::{EdgeLabel, UnGraph, triconnected::get_triconnected_components};
+use std::time::{Duration, Instant};
+
+fn giant_bond(k: usize) -> UnGraph {
+    let mut graph = UnGraph::new_undirected();
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    for _ in 0..k {
+        graph.add_edge(a, b, EdgeLabel::Real);
+    }
+    graph.add_edge(b, c, EdgeLabel::Real);
+    graph.add_edge(c, a, EdgeLabel::Real);
+    graph
+}
+
+// the best of a few runs, so that a single slow run doesn't matter
+fn measure(graph: &UnGraph) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            let tricon = get_triconnected_components(graph);
+            let elapsed = start.elapsed();
+            assert_eq!(tricon.comp.len(), 2);
+            elapsed
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    println!("{:>10} {:>12} {:>12}", "edges", "time", "ns per edge");
+    for k in [100_000, 200_000, 400_000, 800_000] {
+        let graph = giant_bond(k);
+        let time = measure(&graph);
+        println!(
+            "{:>10} {:>12.2?} {:>12.1}",
+            k,
+            time,
+            time.as_nanos() as f64 / k as f64
+        );
+    }
+}
@@ -1,9 +1,10 @@
+use crate::collections::HashSet;
+use crate::types::to_csr_with_edges;
 use crate::{DFSEdgeLabel, EdgeLabel, UnGraph};
 use embed_doc_image::embed_doc_image;
-use hashbrown::HashSet;
 use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::{EdgeRef, NodeIndexable};
-use radsort;
+use std::collections::VecDeque;
 
 /// Represents the block-cut tree of a graph, containing blocks, cut vertices, and their relationships.
 #[derive(Debug, Clone)]
@@ -14,6 +15,12 @@ pub struct BlockCutTree {
     pub cut_count: usize,
     /// Blocks of the graph.
     pub blocks: Vec<UnGraph>,
+    /// Original edge indices of each block: `block_edges[b][i]` is the edge of the input graph
+    /// corresponding to edge `i` of `blocks[b]`.
+    pub block_edges: Vec<Vec<EdgeIndex>>,
+    /// Original vertex indices of each block: `block_vertices[b][i]` is the vertex of the input graph
+    /// corresponding to vertex `i` of `blocks[b]`. Indices are increasing.
+    pub block_vertices: Vec<Vec<usize>>,
     /// Graph of blocks and cut vertices. Blocks have numbers from 0 to block_count - 1.
     /// Cut vertices have numbers from block_count to block_count + cut_count - 1.
     pub graph: UnGraph,
@@ -29,7 +36,234 @@ pub struct BlockCutTree {
     pub preorder: Vec<usize>,
 }
 
-impl BlockCutTree {}
+impl BlockCutTree {
+    /// Returns `true` iff block `block_id` is a bridge, i.e. it has exactly one edge and two vertices.
+    pub fn is_bridge_block(&self, block_id: usize) -> bool {
+        let block = &self.blocks[block_id];
+        block.edge_count() == 1 && block.node_count() == 2
+    }
+
+    /// Returns vertices and edges of block `block_id` in the index space of the input graph.
+    ///
+    /// Vertices are in increasing order, edges are in the same order as edges of `blocks[block_id]`.
+    pub fn block_as_original_indices(&self, block_id: usize) -> (Vec<usize>, Vec<(usize, usize)>) {
+        let vertices = &self.block_vertices[block_id];
+        let edges = self.blocks[block_id]
+            .edge_references()
+            .map(|e| (vertices[e.source().index()], vertices[e.target().index()]))
+            .collect();
+        (vertices.clone(), edges)
+    }
+
+    /// Returns labels of cut vertices separating vertices labeled `a` and `b`, i.e. cut vertices
+    /// on the path between them in the block-cut tree, in order from `a` to `b`.
+    ///
+    /// Removing any of them disconnects `a` from `b`, and these are the only such vertices.
+    /// `a` and `b` themselves are never reported.
+    ///
+    /// Needs `blocks`, so it can't be used on trees restored with [`BlockCutTree::from_compact`].
+    /// Panics if there is no vertex with given label.
+    pub fn separating_cut_vertices(&self, a: u32, b: u32) -> Vec<u32> {
+        let cut_range = self.block_count..self.block_count + self.cut_count;
+        let tree_node = |label: u32| {
+            cut_range
+                .clone()
+                .find(|&c| self.graph[NodeIndex::new(c)] == label)
+                .or_else(|| {
+                    (0..self.block_count)
+                        .find(|&i| self.blocks[i].node_weights().any(|&l| l == label))
+                })
+                .expect("Label should exist")
+        };
+        let (src, dst) = (tree_node(a), tree_node(b));
+
+        let mut parent = vec![usize::MAX; self.graph.node_count()];
+        parent[src] = src;
+        let mut queue = VecDeque::from([src]);
+        while let Some(u) = queue.pop_front() {
+            for v in self.graph.neighbors(NodeIndex::new(u)) {
+                if parent[v.index()] == usize::MAX {
+                    parent[v.index()] = u;
+                    queue.push_back(v.index());
+                }
+            }
+        }
+
+        let mut path = vec![dst];
+        while *path.last().unwrap() != src {
+            path.push(parent[*path.last().unwrap()]);
+        }
+        path.reverse();
+
+        path.into_iter()
+            .filter(|c| cut_range.contains(c))
+            .map(|c| self.graph[NodeIndex::new(c)])
+            .filter(|&label| label != a && label != b)
+            .collect()
+    }
+
+    /// Serializes the tree to a compact, human-readable format:
+    /// - line `block_count cut_count`,
+    /// - one line per block with its vertices (indices of the input graph, see `block_vertices`),
+    /// - one line with labels of cut vertices, in the order of their nodes in `graph`,
+    /// - one line `u v` per edge of `graph`.
+    ///
+    /// Numbers are separated by single spaces.
+    pub fn to_compact(&self) -> String {
+        let join = |values: Vec<String>| values.join(" ");
+
+        let mut output = format!("{} {}\n", self.block_count, self.cut_count);
+        for vertices in &self.block_vertices {
+            output.push_str(&join(vertices.iter().map(|v| v.to_string()).collect()));
+            output.push('\n');
+        }
+        let cuts = (self.block_count..self.block_count + self.cut_count)
+            .map(|c| self.graph[NodeIndex::new(c)].to_string())
+            .collect();
+        output.push_str(&join(cuts));
+        output.push('\n');
+        for e in self.graph.edge_references() {
+            output.push_str(&format!("{} {}\n", e.source().index(), e.target().index()));
+        }
+        output
+    }
+
+    /// Restores a tree saved with [`BlockCutTree::to_compact`].
+    ///
+    /// Only the tree itself is stored, so `block_count`, `cut_count`, `block_vertices`, `graph` and `node_to_id`
+    /// are restored, while `blocks`, `block_edges`, `edge_labels` and `preorder`, which describe the input graph, are empty.
+    ///
+    /// Panics if the input is not in the compact format.
+    pub fn from_compact(input: &str) -> BlockCutTree {
+        let parse = |line: &str| -> Vec<usize> {
+            line.split_whitespace()
+                .map(|x| {
+                    x.parse()
+                        .expect("Wrong format, expected a non-negative number")
+                })
+                .collect()
+        };
+
+        let mut lines = input.lines();
+        let header = parse(
+            lines
+                .next()
+                .expect("Wrong format, expected 'blocks cuts' header"),
+        );
+        let [block_count, cut_count] = header[..] else {
+            panic!("Wrong format, expected 'blocks cuts' header");
+        };
+
+        let block_vertices: Vec<Vec<usize>> = (0..block_count)
+            .map(|_| {
+                parse(
+                    lines
+                        .next()
+                        .expect("Wrong format, expected a line of block vertices"),
+                )
+            })
+            .collect();
+        let cut_labels = parse(
+            lines
+                .next()
+                .expect("Wrong format, expected a line of cut labels"),
+        );
+        assert_eq!(
+            cut_labels.len(),
+            cut_count,
+            "Wrong format, wrong number of cut labels"
+        );
+
+        let mut graph = UnGraph::new_undirected();
+        for i in 0..block_count {
+            graph.add_node(i as u32);
+        }
+        for &label in &cut_labels {
+            graph.add_node(label as u32);
+        }
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let [u, v] = parse(line)[..] else {
+                panic!("Wrong format, expected 'u v' for a skeleton edge");
+            };
+            graph.add_edge(NodeIndex::new(u), NodeIndex::new(v), EdgeLabel::Structure);
+        }
+
+        let n = block_vertices
+            .iter()
+            .flatten()
+            .map(|&v| v + 1)
+            .max()
+            .unwrap_or(0);
+        let mut node_to_id = vec![0; n];
+        for (i, vertices) in block_vertices.iter().enumerate() {
+            for &v in vertices {
+                node_to_id[v] = i;
+            }
+        }
+        // a cut vertex is the only vertex shared by two of its blocks
+        for c in block_count..block_count + cut_count {
+            let mut blocks = graph.neighbors(NodeIndex::new(c)).map(|b| b.index());
+            let (a, b) = (blocks.next().unwrap(), blocks.next().unwrap());
+            let v = *block_vertices[a]
+                .iter()
+                .find(|v| block_vertices[b].contains(v))
+                .expect("Blocks of a cut vertex should share it");
+            node_to_id[v] = c;
+        }
+
+        BlockCutTree {
+            block_count,
+            cut_count,
+            blocks: vec![],
+            block_edges: vec![],
+            block_vertices,
+            graph,
+            node_to_id,
+            edge_labels: vec![],
+            preorder: vec![],
+        }
+    }
+}
+
+/// `(preorder, edge_labels, is_cut)` at some step of [`dfs`].
+type DfsSnapshot = (Vec<usize>, Vec<DFSEdgeLabel>, Vec<bool>);
+
+/// State of [`dfs`] shared by all recursive calls.
+struct DfsState {
+    time: usize,
+    preorder: Vec<usize>,
+    edge_labels: Vec<DFSEdgeLabel>,
+    edge_stack: Vec<usize>,
+    // block is defined by set of edges, this way we avoid problem with cut vertices multi membership
+    blocks: Vec<Vec<usize>>,
+    is_cut: Vec<bool>,
+    // snapshots after every step of the DFS, collected only if `Some`
+    snapshots: Option<Vec<DfsSnapshot>>,
+}
+
+impl DfsState {
+    fn new(n: usize, m: usize) -> Self {
+        DfsState {
+            time: 0,
+            preorder: vec![usize::MAX; n],
+            edge_labels: vec![DFSEdgeLabel::Unvisited; m],
+            edge_stack: Vec::with_capacity(m),
+            blocks: Vec::new(),
+            is_cut: vec![false; n],
+            snapshots: None,
+        }
+    }
+
+    fn snapshot(&mut self) {
+        if let Some(snapshots) = &mut self.snapshots {
+            snapshots.push((
+                self.preorder.clone(),
+                self.edge_labels.clone(),
+                self.is_cut.clone(),
+            ));
+        }
+    }
+}
 
 /// Returns the lowest preorder vertex reachable from subtree of u [lowpoint].
 ///
@@ -44,68 +278,62 @@ impl BlockCutTree {}
 ///
 /// </div>
 fn dfs(
-    graph: &UnGraph,
+    // CSR adjacency of the graph (row offsets, (neighbor, edge id)), see `to_csr`
+    csr: &(Vec<usize>, Vec<(usize, usize)>),
     // NodeIndex not label!!!
     u: usize,
     parent: Option<usize>,
-    time: &mut usize,
-    preorder: &mut [usize],
-    edge_labels: &mut [DFSEdgeLabel],
-    edge_stack: &mut Vec<usize>,
-    // block is defined by set of edges, this way we avoid problem with cut vertices multi membership
-    blocks: &mut Vec<Vec<usize>>,
-    is_cut: &mut [bool],
+    state: &mut DfsState,
 ) -> usize {
-    preorder[u] = *time;
-    *time += 1;
-    let mut low = preorder[u];
+    state.preorder[u] = state.time;
+    state.time += 1;
+    state.snapshot();
+    let mut low = state.preorder[u];
     let mut children = 0;
 
     // process all edges of u to get true lowpoint of u
-    for e in graph.edges(NodeIndex::new(u)) {
-        let v = e.target().index();
-        if preorder[v] == usize::MAX {
+    let (offsets, adj) = csr;
+    for &(v, eid) in &adj[offsets[u]..offsets[u + 1]] {
+        let mut step = false;
+        if state.preorder[v] == usize::MAX {
             // v is not visited yet
-            edge_labels[e.id().index()] = DFSEdgeLabel::Tree;
+            state.edge_labels[eid] = DFSEdgeLabel::Tree;
             children += 1;
 
-            let stack_len = edge_stack.len();
-            edge_stack.push(e.id().index());
-
-            let low_v = dfs(
-                graph,
-                v,
-                Some(u),
-                time,
-                preorder,
-                edge_labels,
-                edge_stack,
-                blocks,
-                is_cut,
-            );
+            let stack_len = state.edge_stack.len();
+            state.edge_stack.push(eid);
+
+            let low_v = dfs(csr, v, Some(u), state);
 
             // maybe some descendant of v has lower lowpoint
             low = low.min(low_v);
-            if low_v >= preorder[u] {
+            if low_v >= state.preorder[u] {
                 // u is a cut vertex or root in both cases we need to process the block
-                is_cut[u] = parent.is_some(); // we are certain that u is a cut vertex
+                state.is_cut[u] = parent.is_some(); // we are certain that u is a cut vertex
                 // by nature of DFS, all edges of biconnected component are on the stack
-                let block = edge_stack[stack_len..].to_vec();
-                edge_stack.truncate(stack_len);
-                blocks.push(block);
+                let block = state.edge_stack[stack_len..].to_vec();
+                state.edge_stack.truncate(stack_len);
+                state.blocks.push(block);
+                step = true;
             }
-        } else if preorder[v] < preorder[u]
-            && edge_labels[e.id().index()] == DFSEdgeLabel::Unvisited
+        } else if state.preorder[v] < state.preorder[u]
+            && state.edge_labels[eid] == DFSEdgeLabel::Unvisited
         {
             // may be parallel edge or back edge
-            edge_stack.push(e.id().index());
-            edge_labels[e.id().index()] = DFSEdgeLabel::Back;
-            low = low.min(preorder[v]);
+            state.edge_stack.push(eid);
+            state.edge_labels[eid] = DFSEdgeLabel::Back;
+            low = low.min(state.preorder[v]);
+            step = true;
         }
 
-        // remember to check if root is a cut vertex
+        // remember to check if root is a cut vertex,
+        // parallel edges to an already visited child are not tree edges so they don't count as children
         if parent.is_none() && children > 1 {
-            is_cut[u] = true;
+            state.is_cut[u] = true;
+        }
+
+        if step {
+            state.snapshot();
         }
     }
 
@@ -120,8 +348,9 @@ fn dfs(
 /// <div class="warning">
 ///
 /// - We consider graph with one vertex and no edges as 1 biconnected component.
+/// - Graph with no vertices has no biconnected components.
 /// - Graph must be connected, otherwise you will get  only first BC tree not the forest.
-/// - We are assuming that graph is simple.
+/// - Parallel edges are allowed, two vertices joined only by parallel edges form one block.
 ///
 /// </div>
 ///
@@ -169,19 +398,40 @@ fn dfs(
 #[embed_doc_image("bc_full", "assets/bc_full.svg")]
 
 pub fn get_block_cut_tree(graph: &UnGraph) -> BlockCutTree {
+    get_block_cut_tree_rooted(graph, 0)
+}
+
+/// Same as [`get_block_cut_tree`], but DFS starts at the vertex with internal index `root`.
+///
+/// Blocks and cut vertices don't depend on the root, but their numbering, preorder and
+/// DFS edge labels do, so this gives reproducible drawings of a chosen traversal.
+///
+/// Panics if `root` is not a vertex of a non-empty graph.
+pub fn get_block_cut_tree_rooted(graph: &UnGraph, root: usize) -> BlockCutTree {
     let graph_size = graph.node_count();
-    let mut time = 0;
-    let mut preorder = vec![usize::MAX; graph_size];
-    let mut edge_labels = vec![DFSEdgeLabel::Unvisited; graph.edge_count()];
-    let mut edge_stack = Vec::with_capacity(graph.edge_count());
-    let mut is_cut = vec![false; graph_size];
-    let mut blocks = Vec::new();
+
+    if graph_size == 0 {
+        return BlockCutTree {
+            block_count: 0,
+            cut_count: 0,
+            blocks: vec![],
+            block_edges: vec![],
+            block_vertices: vec![],
+            graph: UnGraph::new_undirected(),
+            node_to_id: vec![],
+            edge_labels: vec![],
+            preorder: vec![],
+        };
+    }
+    assert!(root < graph_size, "Root must be a vertex of the graph");
 
     if graph_size == 1 && graph.edge_count() == 0 {
         let mut block_cut_tree = BlockCutTree {
             block_count: 1,
             cut_count: 0,
             blocks: vec![UnGraph::new_undirected()],
+            block_edges: vec![vec![]],
+            block_vertices: vec![vec![0]],
             graph: UnGraph::new_undirected(),
             node_to_id: vec![0],
             edge_labels: vec![],
@@ -194,17 +444,15 @@ pub fn get_block_cut_tree(graph: &UnGraph) -> BlockCutTree {
         return block_cut_tree;
     }
 
-    dfs(
-        graph,
-        0, // arbitrary root
-        None,
-        &mut time,
-        &mut preorder,
-        &mut edge_labels,
-        &mut edge_stack,
-        &mut blocks,
-        &mut is_cut,
-    );
+    let mut state = DfsState::new(graph_size, graph.edge_count());
+    dfs(&to_csr_with_edges(graph), root, None, &mut state);
+    let DfsState {
+        preorder,
+        edge_labels,
+        blocks,
+        is_cut,
+        ..
+    } = state;
 
     // Sets of vertices in each block
     let mut blocks_vertices_sets: Vec<HashSet<usize>> = vec![HashSet::new(); blocks.len()];
@@ -216,6 +464,8 @@ pub fn get_block_cut_tree(graph: &UnGraph) -> BlockCutTree {
         block_count: blocks.len(),
         cut_count: 0,
         blocks: Vec::with_capacity(blocks.len()),
+        block_edges: Vec::with_capacity(blocks.len()),
+        block_vertices: Vec::with_capacity(blocks.len()),
         graph: UnGraph::new_undirected(),
         node_to_id: vec![0; graph_size],
         edge_labels,
@@ -237,10 +487,13 @@ pub fn get_block_cut_tree(graph: &UnGraph) -> BlockCutTree {
 
         // Sort them with linear sort to maintain labels and internal indices relation
         let mut block_vertices: Vec<usize> = blocks_vertices_sets[i].iter().copied().collect();
+        #[cfg(feature = "radsort")]
         radsort::sort(&mut block_vertices);
+        #[cfg(not(feature = "radsort"))]
+        block_vertices.sort_unstable();
 
         // And just insert labels to the block graph
-        for u in block_vertices {
+        for &u in &block_vertices {
             let label = graph.node_weight(NodeIndex::new(u)).unwrap().clone();
             bicon_internal_indices[u] = block_graph.add_node(label).index();
             block_cut_tree.node_to_id[u] = i;
@@ -262,6 +515,10 @@ pub fn get_block_cut_tree(graph: &UnGraph) -> BlockCutTree {
 
         block_cut_tree.graph.add_node(i.try_into().unwrap());
         block_cut_tree.blocks.push(block_graph);
+        block_cut_tree
+            .block_edges
+            .push(block.iter().map(|&e| EdgeIndex::new(e)).collect());
+        block_cut_tree.block_vertices.push(block_vertices);
     }
 
     // Add cut vertices as nodes
@@ -293,6 +550,73 @@ pub fn get_block_cut_tree(graph: &UnGraph) -> BlockCutTree {
     block_cut_tree
 }
 
+/// Returns the bridge tree of the graph and the id of the 2-edge-connected component of each vertex.
+///
+/// Every 2-edge-connected component is contracted to a single node labeled with its id, components are
+/// connected by [`EdgeLabel::Structure`] edges, one for each bridge. While the block-cut tree splits the graph
+/// at cut vertices, the bridge tree splits it at bridges.
+///
+/// Components are numbered in the order of their smallest internal vertex index.
+/// `component[v]` is the component of the vertex with internal index `v`.
+///
+/// # Idea
+/// Bridges are exactly edges of [bridge blocks](BlockCutTree::is_bridge_block),
+/// after removing them the remaining connected components are 2-edge-connected.
+///
+/// # Warning
+/// <div class="warning">
+///
+/// - Graph must be connected, as for [`get_block_cut_tree`].
+/// - Parallel edges are never bridges.
+///
+/// </div>
+pub fn bridge_tree(graph: &UnGraph) -> (UnGraph, Vec<usize>) {
+    let bct = get_block_cut_tree(graph);
+    let mut is_bridge = vec![false; graph.edge_count()];
+    for b in 0..bct.block_count {
+        if bct.is_bridge_block(b) {
+            is_bridge[bct.block_edges[b][0].index()] = true;
+        }
+    }
+
+    let n = graph.node_count();
+    let mut component = vec![usize::MAX; n];
+    let mut tree = UnGraph::new_undirected();
+    for start in 0..n {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        let id = tree.add_node(tree.node_count() as u32).index();
+        component[start] = id;
+        let mut queue = VecDeque::from([start]);
+        while let Some(u) = queue.pop_front() {
+            for e in graph.edges(NodeIndex::new(u)) {
+                let v = if e.source().index() == u {
+                    e.target().index()
+                } else {
+                    e.source().index()
+                };
+                if !is_bridge[e.id().index()] && component[v] == usize::MAX {
+                    component[v] = id;
+                    queue.push_back(v);
+                }
+            }
+        }
+    }
+
+    for e in graph.edge_references() {
+        if is_bridge[e.id().index()] {
+            tree.add_edge(
+                NodeIndex::new(component[e.source().index()]),
+                NodeIndex::new(component[e.target().index()]),
+                EdgeLabel::Structure,
+            );
+        }
+    }
+
+    (tree, component)
+}
+
 /// Output a skeleton of the block-cut tree in DOT format.
 /// Biconnected components (blocks) are represented as green nodes labeled B_i.
 /// Cut vertices are represented as red nodes with their real labels.
@@ -412,53 +736,128 @@ pub fn draw_full_block_cut_tree(bct: &BlockCutTree) -> String {
 
 /// Draws the DFS tree and indicates cut vertices.
 ///
-/// Tree edges are drawn in solid lines, back edges in dashed lines.
+/// Tree edges are drawn in solid lines, back edges in dashed lines, edges not visited by the DFS
+/// (self-loops) in dotted lines.
 ///
 /// Cut vertices are colored red.
 ///
 /// Intended to use with `dot`.
 pub fn draw_bc_tree_dfs(graph: &UnGraph, bc_tree: &BlockCutTree) -> String {
-    let mut output = String::from("digraph {\n");
-    output.push_str("  rankdir=TD;\n");
-    output.push_str("  node [style=filled, shape=circle];\n");
+    draw_dfs(graph, bc_tree, None)
+}
 
-    for (i, node) in graph.node_indices().enumerate() {
-        let label = graph.node_weight(node).unwrap();
-        let color = if bc_tree.node_to_id[node.index()] < bc_tree.block_count {
-            "lightblue"
-        } else {
-            "lightcoral"
-        };
-        output.push_str(&format!(
-            "  {} [label=\"{}\", fillcolor={}];\n",
-            i, label, color
-        ));
+/// Draws the DFS of [`get_block_cut_tree`] step by step, one DOT frame per step, e.g. to assemble an animation.
+///
+/// A step is discovering a vertex (together with the tree edge leading to it), classifying a back edge
+/// or popping a block from the edge stack, so there are at most `n + m` frames.
+/// Frames look like [`draw_bc_tree_dfs`]: undiscovered vertices are white, cut vertices turn red
+/// once they are known to be cut vertices, and edges not classified yet are dotted.
+/// The last frame is the same as the output of [`draw_bc_tree_dfs`].
+pub fn draw_bc_tree_dfs_frames(graph: &UnGraph) -> Vec<String> {
+    let n = graph.node_count();
+    if n == 0 {
+        return vec![];
     }
 
-    // Add edges with labels
-    for edge in graph.edge_references() {
-        let (mut a, mut b) = (edge.source().index(), edge.target().index());
-        let label = bc_tree.edge_labels[edge.id().index()].clone();
-        let style = match label {
-            DFSEdgeLabel::Tree => {
-                if bc_tree.preorder[a] > bc_tree.preorder[b] {
-                    std::mem::swap(&mut a, &mut b);
-                }
-                "solid"
-            }
-            DFSEdgeLabel::Back => {
-                if bc_tree.preorder[a] < bc_tree.preorder[b] {
-                    std::mem::swap(&mut a, &mut b);
-                }
-                "dashed"
-            }
-            _ => "",
-        };
-        output.push_str(&format!("  {} -> {} [style={}];\n", a, b, style));
+    let mut state = DfsState::new(n, graph.edge_count());
+    state.snapshots = Some(Vec::new());
+    dfs(&to_csr_with_edges(graph), 0, None, &mut state);
+
+    state
+        .snapshots
+        .unwrap()
+        .iter()
+        .map(|(preorder, edge_labels, is_cut)| {
+            let node_colors: Vec<&str> = (0..n)
+                .map(|v| {
+                    if preorder[v] == usize::MAX {
+                        "white"
+                    } else if is_cut[v] {
+                        "lightcoral"
+                    } else {
+                        "lightblue"
+                    }
+                })
+                .collect();
+            render_dfs(graph, &node_colors, edge_labels, preorder, None)
+        })
+        .collect()
+}
+
+/// It does the same as `draw_bc_tree_dfs`, but additionally colors edges by the blocks they belong to.
+///
+/// Each block gets a distinct color (hue), so the DFS tree and the block clustering are visible in one figure.
+///
+/// Intended to use with `dot`.
+pub fn draw_bc_tree_dfs_colored(graph: &UnGraph, bc_tree: &BlockCutTree) -> String {
+    let mut edge_colors = vec![String::new(); graph.edge_count()];
+    for (i, edges) in bc_tree.block_edges.iter().enumerate() {
+        let hue = i as f64 / bc_tree.block_count as f64;
+        for e in edges {
+            // full precision, rounding would merge hues of blocks once there are more than 1000 of them
+            edge_colors[e.index()] = format!("{} 0.800 0.800", hue);
+        }
     }
 
-    output.push_str("}\n");
-    output
+    draw_dfs(graph, bc_tree, Some(&edge_colors))
+}
+
+fn draw_dfs(graph: &UnGraph, bc_tree: &BlockCutTree, edge_colors: Option<&[String]>) -> String {
+    let node_colors: Vec<&str> = graph
+        .node_indices()
+        .map(|node| {
+            if bc_tree.node_to_id[node.index()] < bc_tree.block_count {
+                "lightblue"
+            } else {
+                "lightcoral"
+            }
+        })
+        .collect();
+
+    render_dfs(
+        graph,
+        &node_colors,
+        &bc_tree.edge_labels,
+        &bc_tree.preorder,
+        edge_colors,
+    )
+}
+
+fn render_dfs(
+    graph: &UnGraph,
+    node_colors: &[&str],
+    edge_labels: &[DFSEdgeLabel],
+    preorder: &[usize],
+    edge_colors: Option<&[String]>,
+) -> String {
+    crate::output::render_digraph(
+        graph,
+        |e| {
+            let (mut a, mut b) = graph.edge_endpoints(e).unwrap();
+            // tree edges point down and back edges point up
+            let swap = match edge_labels[e.index()] {
+                DFSEdgeLabel::Tree => preorder[a.index()] > preorder[b.index()],
+                DFSEdgeLabel::Back => preorder[a.index()] < preorder[b.index()],
+                _ => false,
+            };
+            if swap {
+                std::mem::swap(&mut a, &mut b);
+            }
+            (a, b)
+        },
+        |v| format!("fillcolor={}", node_colors[v.index()]),
+        |e| {
+            let style = match edge_labels[e.index()] {
+                DFSEdgeLabel::Tree => "solid",
+                DFSEdgeLabel::Back => "dashed",
+                _ => "dotted",
+            };
+            match edge_colors {
+                Some(colors) => format!("style={}, color=\"{}\"", style, colors[e.index()]),
+                None => format!("style={}", style),
+            }
+        },
+    )
 }
 
 #[cfg(test)]
@@ -467,24 +866,9 @@ mod dfs_tests {
     use crate::types::UnGraph;
 
     fn run_dfs(g: &UnGraph, start: usize) -> (Vec<bool>, Vec<Vec<usize>>, Vec<usize>) {
-        let mut time = 0;
-        let mut preorder = vec![usize::MAX; g.node_count()];
-        let mut edge_labels = vec![DFSEdgeLabel::Unvisited; g.edge_count()];
-        let mut edge_stack = Vec::new();
-        let mut blocks = Vec::new();
-        let mut is_cut = vec![false; g.node_count()];
-        dfs(
-            g,
-            start,
-            None,
-            &mut time,
-            &mut preorder,
-            &mut edge_labels,
-            &mut edge_stack,
-            &mut blocks,
-            &mut is_cut,
-        );
-        (is_cut, blocks, preorder)
+        let mut state = DfsState::new(g.node_count(), g.edge_count());
+        dfs(&to_csr_with_edges(g), start, None, &mut state);
+        (state.is_cut, state.blocks, state.preorder)
     }
 
     fn assert_dfs(
@@ -608,6 +992,33 @@ mod dfs_tests {
             &mut [vec![0, 1, 2], vec![3, 4, 5]],
         );
     }
+
+    #[test]
+    fn test_dfs_root_parallel_edges() {
+        let mut g = UnGraph::new_undirected();
+        let a = g.add_node(0);
+        let b = g.add_node(1);
+        let c = g.add_node(2);
+        g.add_edge(a, b, EdgeLabel::Real);
+        g.add_edge(a, b, EdgeLabel::Real); // parallel edge
+        g.add_edge(b, c, EdgeLabel::Real);
+        // 0 =2= 1 -- 2
+
+        // root has only one child despite two edges to it
+        assert_dfs(&g, 0, &[false, true, false], &mut [vec![0, 1], vec![2]]);
+
+        let d = g.add_node(3);
+        g.add_edge(a, d, EdgeLabel::Real);
+        // 3 -- 0 =2= 1 -- 2
+
+        // pendant makes root a cut vertex
+        assert_dfs(
+            &g,
+            0,
+            &[true, true, false, false],
+            &mut [vec![0, 1], vec![2], vec![3]],
+        );
+    }
 }
 
 #[cfg(test)]
@@ -721,6 +1132,279 @@ mod bc_tests {
         edges
     }
 
+    #[test]
+    fn test_bc_tree_dfs_edge_labels() {
+        // there is only one DFS edge label enum, exported both from the crate root and `types`
+        use crate::types::DFSEdgeLabel as TypesDFSEdgeLabel;
+
+        let mut g = UnGraph::new_undirected();
+        let a = g.add_node(0);
+        let b = g.add_node(1);
+        let c = g.add_node(2);
+        g.add_edge(a, b, EdgeLabel::Real);
+        g.add_edge(b, c, EdgeLabel::Real);
+        g.add_edge(c, a, EdgeLabel::Real);
+
+        let bct = get_block_cut_tree(&g);
+        let labels: Vec<TypesDFSEdgeLabel> = bct.edge_labels.clone();
+        assert_eq!(
+            labels,
+            vec![DFSEdgeLabel::Tree, DFSEdgeLabel::Tree, DFSEdgeLabel::Back]
+        );
+
+        let dot = draw_bc_tree_dfs(&g, &bct);
+        assert_eq!(dot.matches("style=solid").count(), 2);
+        assert_eq!(dot.matches("style=dashed").count(), 1);
+    }
+
+    #[test]
+    fn test_bc_tree_rooted_same_cut_vertices() {
+        let graph = crate::input::from_file("assets/bc.in");
+        let cut_labels = |bct: &BlockCutTree| {
+            let mut labels: Vec<u32> = (bct.block_count..bct.block_count + bct.cut_count)
+                .map(|i| bct.graph[NodeIndex::new(i)])
+                .collect();
+            labels.sort();
+            labels
+        };
+        let block_labels = |bct: &BlockCutTree| {
+            let mut blocks: Vec<Vec<u32>> = bct
+                .blocks
+                .iter()
+                .map(|b| b.node_weights().copied().collect())
+                .collect();
+            blocks.sort();
+            blocks
+        };
+
+        let expected = get_block_cut_tree(&graph);
+        for root in 0..graph.node_count() {
+            let bct = get_block_cut_tree_rooted(&graph, root);
+            assert_eq!(bct.preorder[root], 0);
+            assert_eq!(cut_labels(&bct), cut_labels(&expected));
+            assert_eq!(block_labels(&bct), block_labels(&expected));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Root must be a vertex")]
+    fn test_bc_tree_rooted_invalid_root() {
+        let graph = crate::input::from_str("0,1\n");
+        get_block_cut_tree_rooted(&graph, 2);
+    }
+
+    #[test]
+    fn test_bc_tree_empty_graph() {
+        let bct = get_block_cut_tree(&UnGraph::new_undirected());
+        assert_eq!(bct.block_count, 0);
+        assert_eq!(bct.cut_count, 0);
+        assert_eq!(bct.graph.node_count(), 0);
+    }
+
+    #[test]
+    fn test_bc_tree_bridge_blocks() {
+        // triangle 0 - 1 - 2 with bridges 2 - 3 and 3 - 4, and a double edge 4 = 5
+        let graph = crate::input::from_str("0,1\n1,2\n2,0\n2,3\n3,4\n4,5\n4,5\n");
+        let bct = get_block_cut_tree(&graph);
+        assert_eq!(bct.block_count, 4);
+
+        let mut bridges: Vec<Vec<u32>> = (0..bct.block_count)
+            .filter(|&b| bct.is_bridge_block(b))
+            .map(|b| {
+                let mut labels: Vec<u32> = bct.blocks[b].node_weights().copied().collect();
+                labels.sort();
+                labels
+            })
+            .collect();
+        bridges.sort();
+        assert_eq!(bridges, vec![vec![2, 3], vec![3, 4]]);
+    }
+
+    #[test]
+    fn test_bridge_tree() {
+        // cycles 0 - 1 - 2 and 3 - 4 - 5 - 6 joined by the bridge 2 - 3
+        let graph = crate::input::from_str("0,1\n1,2\n2,0\n2,3\n3,4\n4,5\n5,6\n6,3\n");
+        let (tree, component) = bridge_tree(&graph);
+        assert_eq!(tree.node_count(), 2);
+        assert_eq!(tree.edge_count(), 1);
+        assert!(tree.contains_edge(NodeIndex::new(0), NodeIndex::new(1)));
+        assert_eq!(component, vec![0, 0, 0, 1, 1, 1, 1]);
+
+        // same graph as in `test_bc_tree_bridge_blocks`, the double edge is not a bridge
+        let graph = crate::input::from_str("0,1\n1,2\n2,0\n2,3\n3,4\n4,5\n4,5\n");
+        let (tree, component) = bridge_tree(&graph);
+        assert_eq!((tree.node_count(), tree.edge_count()), (3, 2));
+        assert_eq!(component, vec![0, 0, 0, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_bc_tree_block_edges() {
+        let graph = crate::input::from_file("assets/bc.in");
+        let bct = get_block_cut_tree(&graph);
+
+        let mut seen = vec![false; graph.edge_count()];
+        for (block, original) in bct.blocks.iter().zip(&bct.block_edges) {
+            assert_eq!(block.edge_count(), original.len());
+            for (e, &orig) in block.edge_references().zip(original) {
+                let (u, v) = graph.edge_endpoints(orig).unwrap();
+                let mut expected = [graph[u], graph[v]];
+                let mut got = [block[e.source()], block[e.target()]];
+                expected.sort();
+                got.sort();
+                assert_eq!(expected, got);
+
+                assert!(!seen[orig.index()]);
+                seen[orig.index()] = true;
+            }
+        }
+        // blocks partition edges of the graph
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn test_bc_tree_block_as_original_indices() {
+        let graph = crate::input::from_file("assets/bc.in");
+        let bct = get_block_cut_tree(&graph);
+
+        let mut seen = vec![false; graph.edge_count()];
+        for b in 0..bct.block_count {
+            let (vertices, edges) = bct.block_as_original_indices(b);
+            assert!(vertices.windows(2).all(|w| w[0] < w[1]));
+            let labels: Vec<u32> = vertices.iter().map(|&v| graph[NodeIndex::new(v)]).collect();
+            assert_eq!(
+                labels,
+                bct.blocks[b].node_weights().copied().collect::<Vec<_>>()
+            );
+
+            for (&(u, v), &orig) in edges.iter().zip(&bct.block_edges[b]) {
+                assert!(vertices.contains(&u) && vertices.contains(&v));
+                let (a, c) = graph.edge_endpoints(orig).unwrap();
+                let mut expected = [a.index(), c.index()];
+                let mut got = [u, v];
+                expected.sort();
+                got.sort();
+                assert_eq!(expected, got);
+                seen[orig.index()] = true;
+            }
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn test_bc_tree_compact_round_trip() {
+        let graph = crate::input::from_file("assets/bc.in");
+        let bct = get_block_cut_tree(&graph);
+        let compact = bct.to_compact();
+        let reloaded = BlockCutTree::from_compact(&compact);
+
+        assert_eq!(reloaded.block_count, bct.block_count);
+        assert_eq!(reloaded.cut_count, bct.cut_count);
+        let skeleton_edges = |t: &BlockCutTree| {
+            t.graph
+                .edge_references()
+                .map(|e| (e.source().index(), e.target().index()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(skeleton_edges(&reloaded), skeleton_edges(&bct));
+        assert_eq!(
+            reloaded.graph.node_weights().collect::<Vec<_>>(),
+            bct.graph.node_weights().collect::<Vec<_>>()
+        );
+        assert_eq!(reloaded.block_vertices, bct.block_vertices);
+        assert_eq!(reloaded.node_to_id, bct.node_to_id);
+        assert_eq!(reloaded.to_compact(), compact);
+
+        let single = BlockCutTree::from_compact(
+            &get_block_cut_tree(&crate::input::from_str("0,1\n")).to_compact(),
+        );
+        assert_eq!((single.block_count, single.cut_count), (1, 0));
+        assert_eq!(single.graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_separating_cut_vertices() {
+        let graph = crate::input::from_file("assets/bc.in");
+        let bct = get_block_cut_tree(&graph);
+
+        // the bridge 1-2, triangle 2-5-6, big block of 7 and 10, bridge 10-16
+        assert_eq!(bct.separating_cut_vertices(1, 16), vec![2, 7, 10]);
+        assert_eq!(bct.separating_cut_vertices(16, 1), vec![10, 7, 2]);
+        assert_eq!(bct.separating_cut_vertices(3, 6), vec![2]);
+        assert_eq!(bct.separating_cut_vertices(12, 9), vec![8]);
+        // endpoints are not reported, vertices of one block are not separated
+        assert_eq!(bct.separating_cut_vertices(2, 16), vec![7, 10]);
+        assert_eq!(bct.separating_cut_vertices(8, 9), vec![]);
+        assert_eq!(bct.separating_cut_vertices(13, 13), vec![]);
+    }
+
+    #[test]
+    fn test_bc_tree_dfs_frames() {
+        let graph = crate::input::from_file("assets/bc.in");
+        let frames = draw_bc_tree_dfs_frames(&graph);
+
+        assert!(frames.len() <= graph.node_count() + graph.edge_count());
+        // every vertex is discovered in its own frame, every block is popped in its own frame
+        let bct = get_block_cut_tree(&graph);
+        assert!(frames.len() >= graph.node_count() + bct.block_count);
+        assert_eq!(frames.last().unwrap(), &draw_bc_tree_dfs(&graph, &bct));
+
+        // only the root is discovered in the first frame
+        assert_eq!(
+            frames[0].matches("fillcolor=white").count(),
+            graph.node_count() - 1
+        );
+        assert_eq!(
+            frames[0].matches("style=dotted").count(),
+            graph.edge_count()
+        );
+
+        assert_eq!(
+            draw_bc_tree_dfs_frames(&UnGraph::new_undirected()),
+            Vec::<String>::new()
+        );
+        let mut single = UnGraph::new_undirected();
+        single.add_node(7);
+        assert_eq!(
+            draw_bc_tree_dfs_frames(&single).last().unwrap(),
+            &draw_bc_tree_dfs(&single, &get_block_cut_tree(&single))
+        );
+    }
+
+    #[test]
+    fn test_bc_tree_dfs_colored() {
+        let graph = crate::input::from_file("assets/bc.in");
+        let bct = get_block_cut_tree(&graph);
+
+        let dot = draw_bc_tree_dfs_colored(&graph, &bct);
+        let colors: HashSet<&str> = dot
+            .lines()
+            .filter(|l| l.contains("->"))
+            .map(|l| l.split("color=").nth(1).unwrap())
+            .collect();
+        assert_eq!(colors.len(), bct.block_count);
+        assert_eq!(
+            dot.lines().filter(|l| l.contains("->")).count(),
+            graph.edge_count()
+        );
+    }
+
+    #[test]
+    fn test_bc_tree_dfs_colored_many_blocks() {
+        // every edge of a path is a block of its own
+        let input: String = (0..1500).map(|i| format!("{},{}\n", i, i + 1)).collect();
+        let graph = crate::input::from_str(&input);
+        let bct = get_block_cut_tree(&graph);
+        assert_eq!(bct.block_count, 1500);
+
+        let dot = draw_bc_tree_dfs_colored(&graph, &bct);
+        let colors: HashSet<&str> = dot
+            .lines()
+            .filter(|l| l.contains("->"))
+            .map(|l| l.split("color=").nth(1).unwrap())
+            .collect();
+        assert_eq!(colors.len(), bct.block_count);
+    }
+
     #[test]
     fn test_bc_tree_components_are_biconnected_light() {
         for i in 0..100 {
@@ -0,0 +1,13 @@
+//! Hash collections used across the crate.
+//!
+//! With the default `hashbrown` feature these are [`hashbrown`](https://docs.rs/hashbrown) maps and sets,
+//! otherwise they fall back to `std::collections`, so the dependency can be dropped for size-sensitive builds.
+//!
+//! The module is internal, so that features stay additive: public functions and fields use
+//! `std::collections` types regardless of the features enabled.
+
+#[cfg(feature = "hashbrown")]
+pub use hashbrown::{HashMap, HashSet};
+
+#[cfg(not(feature = "hashbrown"))]
+pub use std::collections::{HashMap, HashSet};
@@ -0,0 +1,571 @@
+use crate::collections::HashMap;
+use crate::drawing_blocks::geometry::Point;
+use crate::{EdgeLabel, UnGraph};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+
+/// Represents a face in the dual graph of a planar graph.
+#[derive(Debug, Clone)]
+pub struct Face {
+    /// Order of vertices (clockwise or counterclockwise for outer face)
+    pub order: Vec<usize>,
+    /// Indices of edges
+    pub edges: HashSet<usize>,
+    /// Indices of vertices
+    pub vertices: HashSet<usize>,
+}
+
+impl Face {
+    pub fn new() -> Self {
+        Face {
+            order: vec![],
+            edges: HashSet::new(),
+            vertices: HashSet::new(),
+        }
+    }
+}
+
+/// Represents the dual graph of a planar graph.
+///
+/// Each face is a vertex.
+///
+/// Vertices are connected if their faces share an edge in the original graph.
+#[derive(Debug, Clone)]
+pub struct DualGraph {
+    /// Faces of the dual graph
+    pub faces: Vec<Face>,
+    /// Graph of faces
+    pub graph: UnGraph,
+    /// Index of outer face, faces are reindexed so that it is always `0`
+    pub outer_face: usize,
+    /// Faces on both sides of each edge of the primal graph (equal for bridges)
+    pub edge_faces: Vec<(usize, usize)>,
+    /// Primal edge crossed by each edge of `graph`: `dual_edge_primal[d]` is the index of a primal edge
+    /// separating the faces joined by dual edge `d` (the smallest one if there are several)
+    pub dual_edge_primal: Vec<usize>,
+}
+
+impl DualGraph {
+    /// Returns distances from `sources` to all faces, where moving between faces across
+    /// primal edge `e` costs `weights[e]`, or `None` for faces that can't be reached from `sources`.
+    ///
+    /// Distances are summed in `u64`, so they don't overflow even if all weights are close to `u32::MAX`.
+    pub(crate) fn crossing_distances(
+        &self,
+        sources: &[usize],
+        weights: &[u32],
+    ) -> Vec<Option<u64>> {
+        assert_eq!(weights.len(), self.edge_faces.len());
+
+        let mut adj = vec![vec![]; self.faces.len()];
+        for (e, &(f, g)) in self.edge_faces.iter().enumerate() {
+            if f != g {
+                adj[f].push((g, weights[e] as u64));
+                adj[g].push((f, weights[e] as u64));
+            }
+        }
+
+        let mut dist = vec![None; self.faces.len()];
+        let mut heap = BinaryHeap::new();
+        for &s in sources {
+            dist[s] = Some(0);
+            heap.push(Reverse((0, s)));
+        }
+
+        while let Some(Reverse((d, f))) = heap.pop() {
+            if dist[f] != Some(d) {
+                continue;
+            }
+            for &(g, w) in &adj[f] {
+                if dist[g].is_none_or(|old| d + w < old) {
+                    dist[g] = Some(d + w);
+                    heap.push(Reverse((d + w, g)));
+                }
+            }
+        }
+
+        dist
+    }
+
+    /// Returns the minimal total weight of primal edges crossed by a curve going from face `src` to face `dst`,
+    /// or `None` if `dst` can't be reached from `src`.
+    ///
+    /// `weights[e]` is the cost of crossing the `e`-th edge of the primal graph.
+    /// With all weights equal to `1` this is the number of crossings.
+    pub fn weighted_crossing_distance(
+        &self,
+        src: usize,
+        dst: usize,
+        weights: &[u32],
+    ) -> Option<u64> {
+        self.crossing_distances(&[src], weights)[dst]
+    }
+
+    /// Returns the matrix of face-to-face distances: `dist[f][g]` is the minimal number of primal edges
+    /// crossed by a curve going from face `f` to face `g`, or `None` if `g` can't be reached from `f`.
+    ///
+    /// Runs BFS in the dual from every face, so it takes O(f·(f+e)) time and O(f²) memory,
+    /// where `f` is the number of faces and `e` the number of edges.
+    pub fn all_pairs_face_distance(&self) -> Vec<Vec<Option<usize>>> {
+        let f = self.faces.len();
+        let mut dist = vec![vec![None; f]; f];
+        let mut queue = VecDeque::new();
+
+        for (src, row) in dist.iter_mut().enumerate() {
+            row[src] = Some(0);
+            queue.push_back(src);
+            while let Some(u) = queue.pop_front() {
+                for v in self.graph.neighbors(NodeIndex::new(u)) {
+                    if row[v.index()].is_none() {
+                        row[v.index()] = row[u].map(|d| d + 1);
+                        queue.push_back(v.index());
+                    }
+                }
+            }
+        }
+
+        dist
+    }
+}
+
+/// Returns dual graph of given connected planar graph given locations of vertices.
+///
+/// Parameters:
+/// - `points` - must allow for mapping from index to vertex point in the space (should be unique),
+/// - `graph` - graph.
+///
+/// Based on (https://cp-algorithms.com/geometry/planar.html).
+pub fn get_dual_graph(points: &[Point], graph: &UnGraph) -> DualGraph {
+    let n = points.len();
+    assert!(graph.edge_count() > 0); // no edges => algorithm fails
+
+    let mut adj: Vec<Vec<usize>> = vec![vec![]; n];
+    for (i, e) in graph.edge_references().enumerate() {
+        let (s, t) = (e.source().index(), e.target().index());
+        adj[s].push(i);
+        adj[t].push(i);
+    }
+    let mut used: Vec<Vec<bool>> = adj.iter().map(|v| vec![false; v.len()]).collect();
+    let mut edge_to_face: Vec<Option<usize>> = vec![None; graph.edge_count()];
+    let mut edge_faces = vec![(0, 0); graph.edge_count()];
+
+    fn get_other(i: usize, j: usize, graph: &UnGraph) -> usize {
+        let e = graph.edge_references().nth(j).unwrap();
+        let (s, t) = (e.source().index(), e.target().index());
+        if s == i { t } else { s }
+    }
+
+    // sorting adjacency list for each vertex by polar angle
+    for i in 0..n {
+        let compare = |&el: &usize, &er: &usize| {
+            let vl = get_other(i, el, graph);
+            let vr = get_other(i, er, graph);
+            let pl = points[vl].sub(&points[i]);
+            let pr = points[vr].sub(&points[i]);
+            match (pl.half(), pr.half()) {
+                (hl, hr) if hl != hr => hl.cmp(&hr),
+                _ => pr.cross(&pl).cmp(&0).reverse(),
+            }
+        };
+        adj[i].sort_by(compare);
+    }
+
+    let mut faces = Vec::new();
+    let mut edges_in_dual: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut outer_face = None;
+
+    for i in 0..n {
+        for j in 0..adj[i].len() {
+            if used[i][j] {
+                continue;
+            }
+            let mut face = Face::new();
+            let (mut v, mut e) = (i, j);
+            while !used[v][e] {
+                used[v][e] = true;
+
+                // each edge is traversed twice, once from each side
+                // this fact is  used to build dual graph
+                if let Some(face_id) = edge_to_face[adj[v][e]] {
+                    let primal = edges_in_dual
+                        .entry((face_id, faces.len()))
+                        .or_insert(adj[v][e]);
+                    *primal = (*primal).min(adj[v][e]);
+                    edge_faces[adj[v][e]] = (face_id, faces.len());
+                } else {
+                    edge_to_face[adj[v][e]] = Some(faces.len());
+                }
+                face.order.push(v);
+                face.edges.insert(adj[v][e]);
+                face.vertices.insert(v);
+
+                let ue = adj[v][e];
+                let u = get_other(v, ue, graph);
+                let compare = |&el: &usize, &er: &usize| {
+                    let vl = get_other(u, el, graph);
+                    let vr = get_other(u, er, graph);
+                    let pl = points[vl].sub(&points[u]);
+                    let pr = points[vr].sub(&points[u]);
+                    match (pl.half(), pr.half()) {
+                        (hl, hr) if hl != hr => hl.cmp(&hr),
+                        _ => pr.cross(&pl).cmp(&0).reverse(),
+                    }
+                };
+                let pos = adj[u] // next edge to traverse
+                    .binary_search_by(|&x| compare(&x, &ue))
+                    .unwrap();
+                let mut e1 = pos + 1;
+                if e1 == adj[u].len() {
+                    e1 = 0;
+                }
+                v = u;
+                e = e1;
+            }
+            face.order.reverse();
+            let p1 = points[face.order[0]];
+            let mut sum: i128 = 0;
+            for j in 0..face.order.len() {
+                let p2 = points[face.order[j]];
+                let p3 = points[face.order[(j + 1) % face.order.len()]];
+                sum += p2.cross2(&p1, &p3) as i128;
+            }
+            if sum <= 0 {
+                outer_face = Some(faces.len());
+            }
+            faces.push(face);
+        }
+    }
+
+    // reindex faces so that the outer face is always 0 (swap it with the first face)
+    let outer_face = outer_face.unwrap();
+    let reindex = |f: usize| {
+        if f == outer_face {
+            0
+        } else if f == 0 {
+            outer_face
+        } else {
+            f
+        }
+    };
+    faces.swap(0, outer_face);
+    for (f, g) in edge_faces.iter_mut() {
+        (*f, *g) = (reindex(*f), reindex(*g));
+    }
+    let edges_in_dual: HashMap<(usize, usize), usize> = edges_in_dual
+        .into_iter()
+        .map(|((i, j), primal)| ((reindex(i), reindex(j)), primal))
+        .collect();
+
+    let mut graph = UnGraph::new_undirected();
+    for (i, _) in faces.iter().enumerate() {
+        graph.add_node(i as u32);
+    }
+
+    let mut dual_edge_primal = Vec::new();
+    for ((i, j), primal) in edges_in_dual {
+        if i == j {
+            continue; // degenerate case with outer face or not bijective mapping to points
+        }
+        graph.add_edge(NodeIndex::new(i), NodeIndex::new(j), EdgeLabel::Structure);
+        dual_edge_primal.push(primal);
+    }
+
+    let dual_graph = DualGraph {
+        faces,
+        graph,
+        outer_face: 0,
+        edge_faces,
+        dual_edge_primal,
+    };
+
+    dual_graph
+}
+
+mod tests {
+    #![allow(unused_imports)]
+    use super::*;
+    use crate::testing::grids::{generate_grid_graph, get_arbitrary_embedding_of_grid};
+    use crate::testing::iso_with_labels;
+
+    #[allow(dead_code)]
+    fn get_iso_dual_graph_of_grid(rows: usize, cols: usize) -> UnGraph {
+        assert!(rows > 2 && cols > 2);
+        let mut dual_graph = generate_grid_graph(rows - 1, cols - 1);
+        let outer = dual_graph.add_node(dual_graph.node_count() as u32);
+        for r in 0..rows - 1 {
+            for c in 0..cols - 1 {
+                if r == 0 || r == rows - 2 || c == 0 || c == cols - 2 {
+                    let node = NodeIndex::new(r * (cols - 1) + c);
+                    dual_graph.add_edge(node, outer, EdgeLabel::Structure);
+                }
+            }
+        }
+        // edges of a dual graph are structure edges
+        for label in dual_graph.edge_weights_mut() {
+            *label = EdgeLabel::Structure;
+        }
+
+        dual_graph
+    }
+
+    #[test]
+    fn test_dual_graph_edge() {
+        let mut graph = UnGraph::new_undirected();
+        let a = graph.add_node(0);
+        let b = graph.add_node(1);
+        graph.add_edge(a, b, EdgeLabel::Real);
+        let points = vec![Point::new(0, 0), Point::new(1, 0)];
+        let dual_graph = get_dual_graph(&points, &graph);
+        assert_eq!(dual_graph.graph.node_count(), 1);
+        assert_eq!(dual_graph.graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_dual_graph_triangle() {
+        let mut graph = UnGraph::new_undirected();
+        let a = graph.add_node(0);
+        let b = graph.add_node(1);
+        let c = graph.add_node(2);
+        graph.add_edge(a, b, EdgeLabel::Real);
+        graph.add_edge(b, c, EdgeLabel::Real);
+        graph.add_edge(c, a, EdgeLabel::Real);
+        let points = vec![Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)];
+        let dual_graph = get_dual_graph(&points, &graph);
+
+        assert_eq!(dual_graph.graph.node_count(), 2);
+        assert_eq!(dual_graph.graph.edge_count(), 1);
+
+        // all three edges separate the same two faces, the smallest one labels the dual edge
+        assert_eq!(dual_graph.dual_edge_primal, vec![0]);
+        let (f, g) = dual_graph
+            .graph
+            .edge_endpoints(petgraph::graph::EdgeIndex::new(0))
+            .unwrap();
+        let (f, g) = (f.index().min(g.index()), f.index().max(g.index()));
+        let (ef, eg) = dual_graph.edge_faces[dual_graph.dual_edge_primal[0]];
+        assert_eq!((ef.min(eg), ef.max(eg)), (f, g));
+    }
+
+    #[test]
+    fn test_dual_graph_outer_face_first() {
+        for (rows, cols) in [(2, 2), (3, 4), (5, 3)] {
+            let graph = generate_grid_graph(rows, cols);
+            let points = get_arbitrary_embedding_of_grid(rows, cols);
+            let dual_graph = get_dual_graph(&points, &graph);
+
+            assert_eq!(dual_graph.outer_face, 0);
+            // the outer face of a grid goes around its whole boundary
+            assert_eq!(dual_graph.faces[0].order.len(), 2 * (rows + cols) - 4);
+
+            // faces on both sides of every primal edge are adjacent in the dual
+            for &(f, g) in &dual_graph.edge_faces {
+                assert!(
+                    dual_graph.faces[f]
+                        .edges
+                        .iter()
+                        .any(|e| dual_graph.faces[g].edges.contains(e))
+                );
+                if f != g {
+                    assert!(
+                        dual_graph
+                            .graph
+                            .contains_edge(NodeIndex::new(f), NodeIndex::new(g))
+                    );
+                }
+            }
+        }
+
+        // 3x3 grid with the center as vertex 0, so faces are found starting from an inner one
+        let grid = generate_grid_graph(3, 3);
+        let mut points = get_arbitrary_embedding_of_grid(3, 3);
+        points.swap(0, 4);
+        let swap = |x: NodeIndex| NodeIndex::new([4, 1, 2, 3, 0, 5, 6, 7, 8][x.index()]);
+        let mut graph = UnGraph::new_undirected();
+        for i in 0..9 {
+            graph.add_node(i);
+        }
+        for e in grid.edge_references() {
+            graph.add_edge(swap(e.source()), swap(e.target()), EdgeLabel::Real);
+        }
+        let dual_graph = get_dual_graph(&points, &graph);
+        assert_eq!(dual_graph.outer_face, 0);
+        assert_eq!(dual_graph.faces[0].order.len(), 8);
+        assert_eq!(dual_graph.graph.neighbors(NodeIndex::new(0)).count(), 4);
+    }
+
+    #[test]
+    fn test_dual_graph_square() {
+        let graph = generate_grid_graph(2, 2);
+        let points = get_arbitrary_embedding_of_grid(2, 2);
+        let dual_graph = get_dual_graph(&points, &graph);
+
+        assert_eq!(dual_graph.graph.node_count(), 2);
+        assert_eq!(dual_graph.graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_dual_graph_grids() {
+        for rows in 3..20 {
+            for cols in 3..20 {
+                let graph = generate_grid_graph(rows, cols);
+                let points = get_arbitrary_embedding_of_grid(rows, cols);
+                let dual_graph = get_dual_graph(&points, &graph);
+                let iso_dual_graph = get_iso_dual_graph_of_grid(rows, cols);
+                assert!(iso_with_labels(&iso_dual_graph, &dual_graph.graph));
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_crossing_distance() {
+        // 2x3 grid: two square faces sharing the middle edge, surrounded by the outer face
+        let graph = generate_grid_graph(2, 3);
+        let points = get_arbitrary_embedding_of_grid(2, 3);
+        let dual_graph = get_dual_graph(&points, &graph);
+        assert_eq!(dual_graph.faces.len(), 3);
+
+        let inner: Vec<usize> = (0..3).filter(|&f| f != dual_graph.outer_face).collect();
+        let (src, dst) = (inner[0], inner[1]);
+        let shared = dual_graph
+            .edge_faces
+            .iter()
+            .position(|&(f, g)| (f == src && g == dst) || (f == dst && g == src))
+            .unwrap();
+
+        let mut weights = vec![1; graph.edge_count()];
+        assert_eq!(
+            dual_graph.weighted_crossing_distance(src, dst, &weights),
+            Some(1)
+        );
+
+        // crossing the shared edge is now more expensive than going through the outer face
+        weights[shared] = 10;
+        assert_eq!(
+            dual_graph.weighted_crossing_distance(src, dst, &weights),
+            Some(2)
+        );
+        assert_eq!(
+            dual_graph.weighted_crossing_distance(src, src, &weights),
+            Some(0)
+        );
+
+        // no face is reachable without a source
+        assert!(
+            dual_graph
+                .crossing_distances(&[], &weights)
+                .iter()
+                .all(Option::is_none)
+        );
+    }
+
+    #[test]
+    fn test_weighted_crossing_distance_large_weights() {
+        // 2x4 grid: three square faces in a row, the outer one around them
+        let graph = generate_grid_graph(2, 4);
+        let points = get_arbitrary_embedding_of_grid(2, 4);
+        let dual_graph = get_dual_graph(&points, &graph);
+        assert_eq!(dual_graph.faces.len(), 4);
+
+        // the left and the right square are two crossings apart, the sum doesn't fit in u32
+        let weight = u32::MAX / 2 + 1;
+        let weights = vec![weight; graph.edge_count()];
+        let squares: Vec<usize> = (0..4).filter(|&f| f != dual_graph.outer_face).collect();
+        let far = squares
+            .iter()
+            .flat_map(|&f| squares.iter().map(move |&g| (f, g)))
+            .find(|&(f, g)| {
+                dual_graph.faces[f]
+                    .edges
+                    .is_disjoint(&dual_graph.faces[g].edges)
+            })
+            .unwrap();
+        assert_eq!(
+            dual_graph.weighted_crossing_distance(far.0, far.1, &weights),
+            Some(2 * weight as u64)
+        );
+        assert_eq!(
+            dual_graph.weighted_crossing_distance(far.0, dual_graph.outer_face, &weights),
+            Some(weight as u64)
+        );
+    }
+
+    #[test]
+    fn test_all_pairs_face_distance() {
+        // 4x4 grid: 3x3 inner faces surrounded by the outer face
+        let graph = generate_grid_graph(4, 4);
+        let points = get_arbitrary_embedding_of_grid(4, 4);
+        let dual_graph = get_dual_graph(&points, &graph);
+        let dist = dual_graph.all_pairs_face_distance();
+
+        let face = |vertices: [usize; 4]| {
+            (0..dual_graph.faces.len())
+                .find(|&f| {
+                    dual_graph.faces[f].order.len() == 4
+                        && vertices
+                            .iter()
+                            .all(|v| dual_graph.faces[f].vertices.contains(v))
+                })
+                .unwrap()
+        };
+        let top_left = face([0, 1, 4, 5]);
+        let top_middle = face([1, 2, 5, 6]);
+        let center = face([5, 6, 9, 10]);
+        let bottom_right = face([10, 11, 14, 15]);
+        let outer = dual_graph.outer_face;
+
+        assert_eq!(dist[top_left][top_left], Some(0));
+        assert_eq!(dist[top_left][top_middle], Some(1));
+        assert_eq!(dist[top_left][center], Some(2));
+        assert_eq!(dist[outer][center], Some(2));
+        // going around through the outer face is shorter than through the center
+        assert_eq!(dist[top_left][bottom_right], Some(2));
+        let weights = vec![1; graph.edge_count()];
+        for (f, row) in dist.iter().enumerate() {
+            for (g, &d) in row.iter().enumerate() {
+                assert_eq!(d, dist[g][f]);
+                assert_eq!(
+                    d.map(|d| d as u64),
+                    dual_graph.weighted_crossing_distance(f, g, &weights)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_concave() {
+        let mut graph = UnGraph::new_undirected();
+        let a = graph.add_node(0);
+        let b = graph.add_node(1);
+        let c = graph.add_node(2);
+        let d = graph.add_node(3);
+        let e = graph.add_node(4);
+        let f = graph.add_node(5);
+        let g = graph.add_node(6);
+        let h = graph.add_node(7);
+
+        graph.add_edge(a, b, EdgeLabel::Real);
+        graph.add_edge(b, c, EdgeLabel::Real);
+        graph.add_edge(c, d, EdgeLabel::Real);
+        graph.add_edge(d, e, EdgeLabel::Real);
+        graph.add_edge(e, f, EdgeLabel::Real);
+        graph.add_edge(f, g, EdgeLabel::Real);
+        graph.add_edge(g, h, EdgeLabel::Real);
+        graph.add_edge(h, a, EdgeLabel::Real);
+
+        let points = vec![
+            Point::new(0, 0),
+            Point::new(1, 0),
+            Point::new(2, 0),
+            Point::new(2, -1),
+            Point::new(2, -2),
+            Point::new(1, -2),
+            Point::new(1, -1),
+            Point::new(0, -1),
+        ];
+
+        let dual_graph = get_dual_graph(&points, &graph);
+        println!("{:?}", dual_graph.faces);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::types::DiGraph;
+use petgraph::visit::{EdgeRef, NodeIndexable};
+
+/// A point with integer coordinates, e.g. a vertex of a straight-line drawing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    x: i64,
+    y: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn x(&self) -> i64 {
+        self.x
+    }
+
+    pub fn y(&self) -> i64 {
+        self.y
+    }
+
+    pub fn sub(&self, p: &Point) -> Point {
+        Point::new(self.x - p.x, self.y - p.y)
+    }
+
+    pub fn cross(&self, p: &Point) -> i64 {
+        self.x * p.y - self.y * p.x
+    }
+
+    pub fn cross2(&self, p: &Point, q: &Point) -> i64 {
+        p.sub(self).cross(&q.sub(self))
+    }
+
+    pub fn half(&self) -> bool {
+        self.y < 0 || (self.y == 0 && self.x < 0)
+    }
+}
+
+/// Returns the cross product of vectors `ab` and `ac`.
+///
+/// Positive if `a -> b -> c` is a counterclockwise turn, negative if clockwise and `0` if the points are collinear.
+pub fn ccw(a: (i64, i64), b: (i64, i64), c: (i64, i64)) -> i64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Returns true iff `c` lies inside the bounding box of segment `ab`.
+///
+/// Together with `ccw(a, b, c) == 0` it means that `c` lies on the segment.
+pub fn on_segment(a: (i64, i64), b: (i64, i64), c: (i64, i64)) -> bool {
+    c.0 >= a.0.min(b.0) && c.0 <= a.0.max(b.0) && c.1 >= a.1.min(b.1) && c.1 <= a.1.max(b.1)
+}
+
+/// Returns true iff segments `p1p2` and `p3p4` have a common point (including touching and overlapping).
+pub fn do_lines_intersect(p1: (i64, i64), p2: (i64, i64), p3: (i64, i64), p4: (i64, i64)) -> bool {
+    let o1 = ccw(p1, p2, p3);
+    let o2 = ccw(p1, p2, p4);
+    let o3 = ccw(p3, p4, p1);
+    let o4 = ccw(p3, p4, p2);
+
+    // General crossing
+    if o1 * o2 < 0 && o3 * o4 < 0 {
+        return true;
+    }
+
+    // Collinear cases
+    (o1 == 0 && on_segment(p1, p2, p3))
+        || (o2 == 0 && on_segment(p1, p2, p4))
+        || (o3 == 0 && on_segment(p3, p4, p1))
+        || (o4 == 0 && on_segment(p3, p4, p2))
+}
+
+/// Checks whether given coordinates form a valid straight-line planar drawing of `g`.
+///
+/// The drawing is valid iff:
+/// - vertices have distinct coordinates,
+/// - edges without a common endpoint do not intersect,
+/// - no vertex lies on an edge it doesn't belong to.
+///
+/// `coords[v]` is the position of vertex with index `v`. Works in O(n^2 + m^2).
+pub fn is_planar_drawing(g: &DiGraph, coords: &[(i64, i64)]) -> bool {
+    let n = g.node_count();
+    assert!(coords.len() >= n, "Every vertex needs coordinates");
+
+    let edges: Vec<_> = g
+        .edge_references()
+        .map(|e| (g.to_index(e.source()), g.to_index(e.target())))
+        .collect();
+
+    // 1. Vertices must not overlap
+    for u in 0..n {
+        for v in u + 1..n {
+            if coords[u] == coords[v] {
+                return false;
+            }
+        }
+    }
+
+    // 2. Disjoint edges must not interact
+    for i in 0..edges.len() {
+        for j in i + 1..edges.len() {
+            let (u1, v1) = edges[i];
+            let (u2, v2) = edges[j];
+
+            // Ignore edges sharing endpoints
+            if u1 == u2 || u1 == v2 || v1 == u2 || v1 == v2 {
+                continue;
+            }
+
+            if do_lines_intersect(coords[u1], coords[v1], coords[u2], coords[v2]) {
+                return false;
+            }
+        }
+    }
+
+    // 3. Vertex must not lie on an edge
+    for w in 0..n {
+        for &(u, v) in &edges {
+            if w == u || w == v {
+                continue;
+            }
+
+            if ccw(coords[u], coords[v], coords[w]) == 0
+                && on_segment(coords[u], coords[v], coords[w])
+            {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing_blocks::{schnyder::draw, triangulate::triangulate};
+    use crate::input::from_str;
+
+    #[test]
+    fn test_is_planar_drawing_schnyder() {
+        let g = from_str("0,1\n1,2\n2,3\n3,0\n0,2\n1,4\n4,2\n");
+        let triangulated = triangulate(&g);
+        let drawing = draw(&triangulated);
+
+        assert!(is_planar_drawing(&triangulated, &drawing.coordinates));
+
+        // collapse the whole drawing onto a line
+        let collapsed: Vec<_> = (0..triangulated.node_count())
+            .map(|i| (i as i64, 0))
+            .collect();
+        assert!(!is_planar_drawing(&triangulated, &collapsed));
+
+        // and onto a single point
+        let collapsed = vec![(0, 0); triangulated.node_count()];
+        assert!(!is_planar_drawing(&triangulated, &collapsed));
+    }
+}
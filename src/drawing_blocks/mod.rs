@@ -1,5 +1,9 @@
 pub mod circular_list;
+pub mod dual_graph;
 pub mod faces;
+pub mod geometry;
 pub mod schnyder;
 pub mod triangulate;
 pub mod visualize;
+
+pub use schnyder::draw_from_rotation;
@@ -1,6 +1,8 @@
 use super::circular_list::CircularList;
+use super::faces::get_faces;
+use super::triangulate::{embedding_of_rotation, triangulate_rotation};
 use crate::types::DiGraph;
-use petgraph::visit::NodeIndexable;
+use petgraph::visit::{EdgeRef, NodeIndexable};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Color {
@@ -52,28 +54,161 @@ impl SchnyderTree {
 pub struct DrawingResult {
     pub coordinates: Vec<(i64, i64)>,
     pub edge_colors: Vec<(usize, usize, Color)>,
+    pub realizer: SchnyderRealizer,
 }
 
-pub fn draw(g: &DiGraph) -> DrawingResult {
-    let n = g.node_count();
+/// Three trees of the Schnyder realizer (red, blue and green, in this order) used to compute the drawing.
+///
+/// Tree `i` is rooted at `roots[i]` and spans all vertices, `parents[i][v]` is the parent of `v` in it
+/// or `usize::MAX` for the root. Edges of the outer triangle are included, so they belong to two trees.
+pub struct SchnyderRealizer {
+    pub roots: [usize; 3],
+    pub parents: [Vec<usize>; 3],
+}
+
+impl SchnyderRealizer {
+    /// Generates a Graphviz DOT representation of tree `i`, edges go from children to parents.
+    pub fn visualize_realizer_tree(&self, i: usize) -> String {
+        let color = ["red", "blue", "green"][i];
+
+        let mut output = String::from("digraph {\n");
+        output.push_str("  node [shape=circle];\n");
+        for v in 0..self.parents[i].len() {
+            if v == self.roots[i] {
+                output.push_str(&format!("  {} [style=filled, fillcolor={}];\n", v, color));
+            } else {
+                output.push_str(&format!("  {};\n", v));
+            }
+        }
+        for (v, &p) in self.parents[i].iter().enumerate() {
+            if p != usize::MAX {
+                output.push_str(&format!("  {} -> {} [color={}];\n", v, p, color));
+            }
+        }
+        output.push_str("}\n");
+        output
+    }
+}
 
-    let f0 = 0;
+impl DrawingResult {
+    /// Returns width and height of the smallest grid containing all vertices.
+    ///
+    /// For drawings produced by [`draw`] both are at most `n - 2`.
+    pub fn grid_size(&self) -> (i64, i64) {
+        if self.coordinates.is_empty() {
+            return (0, 0);
+        }
 
-    // We need neighbors of f0.
-    let neighbors: Vec<usize> = g
-        .neighbors(g.from_index(f0))
-        .map(|x| g.to_index(x))
-        .collect();
+        let (mut min_x, mut min_y) = (i64::MAX, i64::MAX);
+        let (mut max_x, mut max_y) = (i64::MIN, i64::MIN);
+        for &(x, y) in &self.coordinates {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+        }
+        (max_x - min_x, max_y - min_y)
+    }
+
+    /// Returns the area of the smallest axis-parallel rectangle containing all vertices.
+    pub fn bounding_box_area(&self) -> i64 {
+        let (w, h) = self.grid_size();
+        w * h
+    }
 
-    if neighbors.len() < 2 {
+    /// Returns the sum of Euclidean lengths of all edges of `g` drawn with straight lines.
+    ///
+    /// `g` is an embedding, so every edge is stored as two half-edges and is counted once.
+    /// Together with [`DrawingResult::bounding_box_area`] it allows comparing layouts of the same graph.
+    pub fn total_edge_length(&self, g: &DiGraph) -> f64 {
+        g.edge_references()
+            .map(|e| (g.to_index(e.source()), g.to_index(e.target())))
+            .filter(|&(u, v)| u < v)
+            .map(|(u, v)| {
+                let (x1, y1) = self.coordinates[u];
+                let (x2, y2) = self.coordinates[v];
+                (((x1 - x2).pow(2) + (y1 - y2).pow(2)) as f64).sqrt()
+            })
+            .sum()
+    }
+}
+
+/// Computes Schnyder drawing of a triangulated graph on `(n-2) x (n-2)` grid.
+///
+/// Outer face is the first face returned by [`get_faces`], the one to the right of the half-edge
+/// from vertex `0` to its first neighbor.
+pub fn draw(g: &DiGraph) -> DrawingResult {
+    let faces = get_faces(g);
+    if faces.is_empty() {
         panic!("Graph must be triangulated (degree >= 2)");
     }
+    draw_with_outer_face(g, &faces[0].order)
+}
 
-    // Since it's triangulated, any two consecutive neighbors of a node form a face with that node.
-    let f1 = neighbors[0];
-    let f2 = neighbors[1];
+/// Computes Schnyder drawing of a planar embedding with `boundary` on the outer face.
+///
+/// `boundary` has to be a face of `g`, i.e. one of [`get_faces`] up to a cyclic shift and direction.
+/// This way drawing can agree with another face labeling, e.g. `boundary` can be the order of the outer
+/// face of `get_dual_graph`, as long as `g` is the embedding given by the same points.
+///
+/// `g` is triangulated with [`triangulate_rotation`], which only adds chords inside faces, so the outer
+/// triangle is the one bounded by the first half-edge of `boundary` and the whole face stays outside.
+/// Coordinates are given for the vertices of `g` and `edge_colors` cover the edges of the triangulation.
+///
+/// Panics if `boundary` is not a face of `g`.
+pub fn draw_with_outer_face(g: &DiGraph, boundary: &[usize]) -> DrawingResult {
+    let face = get_faces(g)
+        .into_iter()
+        .find(|face| same_cycle(&face.order, boundary))
+        .expect("Boundary should be a face of the embedding");
+
+    let rotation: Vec<Vec<usize>> = (0..g.node_count())
+        .map(|u| {
+            g.neighbors(g.from_index(u))
+                .map(|x| g.to_index(x))
+                .collect()
+        })
+        .collect();
+    let triangulated = triangulate_rotation(&rotation);
+
+    // the triangle to the right of the same half-edge lies inside the outer face
+    let (a, b) = (face.order[0], face.order[1 % face.order.len()]);
+    let triangle = get_faces(&triangulated)
+        .into_iter()
+        .find(|t| (0..3).any(|i| t.order[i] == a && t.order[(i + 1) % 3] == b))
+        .expect("Triangulation should keep every half-edge");
+
+    // the face is walked f0 -> f2 -> f1, so f2 directly follows f1 around f0
+    let [f0, f2, f1] = [triangle.order[0], triangle.order[1], triangle.order[2]];
+    draw_with_outer_triangle(&triangulated, [f0, f1, f2])
+}
 
-    let f = [f0, f1, f2];
+/// Computes Schnyder drawing of a planar graph given by a rotation system, with `outer_face` on the outside.
+///
+/// The rotation is triangulated combinatorially with [`triangulate_rotation`], so the planarity test
+/// is not run again, e.g. for rotation systems that were already validated.
+/// `outer_face` has to be a face of the rotation system, checked against its face walks, and it stays
+/// the outer face of the drawing, see [`draw_with_outer_face`].
+pub fn draw_from_rotation(rotation: &[Vec<usize>], outer_face: &[usize]) -> DrawingResult {
+    draw_with_outer_face(&embedding_of_rotation(rotation), outer_face)
+}
+
+/// Checks if `a` and `b` are the same cyclic sequence, possibly walked in opposite directions.
+fn same_cycle(a: &[usize], b: &[usize]) -> bool {
+    let k = a.len();
+    if k != b.len() {
+        return false;
+    }
+    let reversed: Vec<usize> = b.iter().rev().copied().collect();
+    (0..k).any(|shift| {
+        [b, &reversed]
+            .iter()
+            .any(|c| (0..k).all(|i| a[(i + shift) % k] == c[i]))
+    })
+}
+
+fn draw_with_outer_triangle(g: &DiGraph, f: [usize; 3]) -> DrawingResult {
+    let n = g.node_count();
 
     let mut list = CircularList::new_disconnected((0..n).collect());
 
@@ -257,114 +392,30 @@ pub fn draw(g: &DiGraph) -> DrawingResult {
         coords.push((c[1], c[2]));
     }
 
+    debug_assert!(
+        coords
+            .iter()
+            .all(|&(x, y)| (0..=n as i64 - 2).contains(&x) && (0..=n as i64 - 2).contains(&y)),
+        "Schnyder drawing should fit in (n-2) x (n-2) grid"
+    );
+
     DrawingResult {
         coordinates: coords,
         edge_colors: edge_colors_list,
+        realizer: SchnyderRealizer {
+            roots: f,
+            parents: trees.map(|t| t.parent),
+        },
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::drawing_blocks::geometry::is_planar_drawing;
     use crate::drawing_blocks::triangulate::triangulate;
     use crate::embedding::is_planar;
     use crate::testing::graph_enumerator::GraphEnumeratorState;
-    use petgraph::visit::EdgeRef;
-
-    // Helper functions for geometry
-    fn ccw(a: (i64, i64), b: (i64, i64), c: (i64, i64)) -> i64 {
-        (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
-    }
-
-    fn on_segment(a: (i64, i64), b: (i64, i64), c: (i64, i64)) -> bool {
-        // Check bounding box
-        c.0 >= a.0.min(b.0) && c.0 <= a.0.max(b.0) && c.1 >= a.1.min(b.1) && c.1 <= a.1.max(b.1)
-    }
-
-    fn do_lines_intersect(p1: (i64, i64), p2: (i64, i64), p3: (i64, i64), p4: (i64, i64)) -> bool {
-        let o1 = ccw(p1, p2, p3);
-        let o2 = ccw(p1, p2, p4);
-        let o3 = ccw(p3, p4, p1);
-        let o4 = ccw(p3, p4, p2);
-
-        // General crossing
-        if o1 * o2 < 0 && o3 * o4 < 0 {
-            return true;
-        }
-
-        // Collinear cases
-        if o1 == 0 && on_segment(p1, p2, p3) {
-            return true;
-        }
-        if o2 == 0 && on_segment(p1, p2, p4) {
-            return true;
-        }
-        if o3 == 0 && on_segment(p3, p4, p1) {
-            return true;
-        }
-        if o4 == 0 && on_segment(p3, p4, p2) {
-            return true;
-        }
-
-        false
-    }
-
-    // Helper to check for overlapping edges that shouldn't differ only by endpoint
-    fn check_intersections(g: &DiGraph, drawing: &DrawingResult) {
-        let edges: Vec<_> = g
-            .edge_references()
-            .map(|e| (g.to_index(e.source()), g.to_index(e.target())))
-            .collect();
-        let n = g.node_count();
-
-        // 1. Check interactions between disjoint edges
-        for i in 0..edges.len() {
-            for j in i + 1..edges.len() {
-                let (u1, v1) = edges[i];
-                let (u2, v2) = edges[j];
-
-                // Ignore edges sharing endpoints
-                if u1 == u2 || u1 == v2 || v1 == u2 || v1 == v2 {
-                    continue;
-                }
-
-                let p1 = drawing.coordinates[u1];
-                let p2 = drawing.coordinates[v1];
-                let p3 = drawing.coordinates[u2];
-                let p4 = drawing.coordinates[v2];
-
-                if do_lines_intersect(p1, p2, p3, p4) {
-                    panic!(
-                        "Disjoint edges interact! {:?} {:?} at coords {:?} {:?} {:?} {:?}",
-                        edges[i], edges[j], p1, p2, p3, p4
-                    );
-                }
-            }
-        }
-
-        // 2. Check vertex lying on edge
-        for i in 0..n {
-            let pv = drawing.coordinates[i];
-            for &(u, v) in &edges {
-                if i == u || i == v {
-                    continue;
-                }
-                let pu = drawing.coordinates[u];
-                let pv_end = drawing.coordinates[v];
-
-                if ccw(pu, pv_end, pv) == 0 && on_segment(pu, pv_end, pv) {
-                    panic!(
-                        "Vertex {} lies on edge {:?}! Coords: {:?} on {:?}-{:?}",
-                        i,
-                        (u, v),
-                        pv,
-                        pu,
-                        pv_end
-                    );
-                }
-            }
-        }
-    }
 
     #[test]
     fn test_schnyder_small_graphs() {
@@ -390,9 +441,227 @@ mod tests {
                     }
 
                     // Verify edge intersections
-                    check_intersections(&triangulated, &drawing);
+                    assert!(is_planar_drawing(&triangulated, &drawing.coordinates));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_schnyder_grid_size() {
+        for n in 3..=6 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: 1 << (n * (n - 1) / 2),
+            };
+
+            for g in enumerator {
+                if !is_planar(&g, false).0 {
+                    continue;
+                }
+                let drawing = draw(&triangulate(&g));
+                let (w, h) = drawing.grid_size();
+
+                let xs = drawing.coordinates.iter().map(|&(x, _)| x);
+                let ys = drawing.coordinates.iter().map(|&(_, y)| y);
+                assert_eq!(w, xs.clone().max().unwrap() - xs.min().unwrap());
+                assert_eq!(h, ys.clone().max().unwrap() - ys.min().unwrap());
+                assert!(w <= n as i64 - 2 && h <= n as i64 - 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_drawing_metrics() {
+        // octahedron, already triangulated
+        let graph =
+            crate::input::from_str("0,1\n0,2\n0,3\n0,4\n5,1\n5,2\n5,3\n5,4\n1,2\n2,3\n3,4\n4,1\n");
+        let triangulated = triangulate(&graph);
+        let drawing = draw(&triangulated);
+
+        let length = drawing.total_edge_length(&triangulated);
+        let area = drawing.bounding_box_area();
+        // vertices have distinct integer coordinates, so every edge has length at least 1
+        assert!(length >= graph.edge_count() as f64);
+        assert!(area > 0);
+        let (w, h) = drawing.grid_size();
+        assert_eq!(area, w * h);
+
+        let again = draw(&triangulate(&graph));
+        assert_eq!(again.total_edge_length(&triangulated), length);
+        assert_eq!(again.bounding_box_area(), area);
+    }
+
+    #[test]
+    fn test_schnyder_realizer_trees_span() {
+        for n in 3..=6 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: 1 << (n * (n - 1) / 2),
+            };
+
+            for g in enumerator {
+                if !is_planar(&g, false).0 {
+                    continue;
+                }
+                let realizer = draw(&triangulate(&g)).realizer;
+
+                for i in 0..3 {
+                    let parent = &realizer.parents[i];
+                    assert_eq!(parent[realizer.roots[i]], usize::MAX);
+                    // walking up from every vertex reaches the root within n steps, so no cycles
+                    for v in 0..n {
+                        let mut u = v;
+                        let mut steps = 0;
+                        while u != realizer.roots[i] {
+                            assert_ne!(parent[u], usize::MAX);
+                            u = parent[u];
+                            steps += 1;
+                            assert!(steps < n);
+                        }
+                    }
+
+                    let dot = realizer.visualize_realizer_tree(i);
+                    assert_eq!(dot.matches(" -> ").count(), n - 1);
                 }
             }
         }
     }
+
+    #[test]
+    fn test_draw_from_rotation_envelope() {
+        let graph = crate::input::from_file("assets/envelope.in");
+        let (planar, embedding) = is_planar(&graph, false);
+        assert!(planar);
+
+        let rotation: Vec<Vec<usize>> = (0..embedding.node_count())
+            .map(|v| {
+                embedding
+                    .neighbors(embedding.from_index(v))
+                    .map(|x| x.index())
+                    .collect()
+            })
+            .collect();
+
+        for face in get_faces(&embedding) {
+            let drawing = draw_from_rotation(&rotation, &face.order);
+            assert!(is_planar_drawing(&embedding, &drawing.coordinates));
+
+            let triangulated = triangulate_rotation(&rotation);
+            assert_eq!(triangulated.edge_count(), 2 * (3 * graph.node_count() - 6));
+            assert!(is_planar_drawing(&triangulated, &drawing.coordinates));
+
+            let outer = outer_face_of_drawing(&graph, &drawing.coordinates);
+            assert!(same_cycle(&outer, &face.order));
+        }
+    }
+
+    #[test]
+    fn test_draw_from_rotation_grid() {
+        use crate::testing::grids::generate_grid_graph;
+
+        // every face of the 3 x 5 grid can be put outside, including the long outer cycle
+        let grid = generate_grid_graph(3, 5);
+        let xy: Vec<(i64, i64)> = (0..15).map(|v| (v % 5, -(v / 5))).collect();
+        let rotation = rotation_of_points(&grid, &xy);
+        let faces = get_faces(&embedding_of_rotation(&rotation));
+        assert_eq!(faces.len(), 9);
+
+        for face in faces {
+            let drawing = draw_from_rotation(&rotation, &face.order);
+            let outer = outer_face_of_drawing(&grid, &drawing.coordinates);
+            assert!(same_cycle(&outer, &face.order));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Boundary should be a face of the embedding")]
+    fn test_draw_from_rotation_not_a_face() {
+        let square = vec![vec![1, 3], vec![2, 0], vec![3, 1], vec![0, 2]];
+        draw_from_rotation(&square, &[0, 2, 1, 3]);
+    }
+
+    // order of the outer face of a straight-line drawing
+    fn outer_face_of_drawing(graph: &crate::UnGraph, coords: &[(i64, i64)]) -> Vec<usize> {
+        use crate::drawing_blocks::dual_graph::get_dual_graph;
+        use crate::drawing_blocks::geometry::Point;
+
+        let points: Vec<Point> = coords.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        let dual = get_dual_graph(&points, graph);
+        dual.faces[dual.outer_face].order.clone()
+    }
+
+    // rotation system of a straight-line drawing, neighbors sorted by angle
+    fn rotation_of_points(graph: &crate::UnGraph, points: &[(i64, i64)]) -> Vec<Vec<usize>> {
+        (0..graph.node_count())
+            .map(|u| {
+                let mut rot: Vec<usize> = graph
+                    .neighbors(graph.from_index(u))
+                    .map(|x| x.index())
+                    .collect();
+                let angle = |v: usize| {
+                    let (dx, dy) = (points[v].0 - points[u].0, points[v].1 - points[u].1);
+                    (dy as f64).atan2(dx as f64)
+                };
+                rot.sort_by(|&v, &w| angle(v).total_cmp(&angle(w)));
+                rot
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_schnyder_outer_face_matches_dual() {
+        use crate::drawing_blocks::dual_graph::get_dual_graph;
+        use crate::testing::grids::{generate_grid_graph, get_arbitrary_embedding_of_grid};
+        use crate::types::embed_with_rotation;
+
+        for (rows, cols) in [(2, 2), (3, 3), (3, 5), (4, 4)] {
+            let grid = generate_grid_graph(rows, cols);
+            let points = get_arbitrary_embedding_of_grid(rows, cols);
+            let dual = get_dual_graph(&points, &grid);
+            let boundary = &dual.faces[dual.outer_face].order;
+
+            let xy: Vec<(i64, i64)> = points.iter().map(|p| (p.x(), p.y())).collect();
+            let embedding = embed_with_rotation(&grid, &rotation_of_points(&grid, &xy));
+            let drawing = draw_with_outer_face(&embedding, boundary);
+            assert!(is_planar_drawing(&embedding, &drawing.coordinates));
+
+            // the dual of the drawing has the same outer face
+            let outer = outer_face_of_drawing(&grid, &drawing.coordinates);
+            assert!(same_cycle(&outer, boundary));
+        }
+
+        // triangle 0, 1, 5 of the triangulated 3 x 5 grid has all vertices on the boundary, but it is inside
+        let grid = generate_grid_graph(3, 5);
+        let xy: Vec<(i64, i64)> = (0..15).map(|v| (v % 5, -(v / 5))).collect();
+        let embedding = embed_with_rotation(&grid, &rotation_of_points(&grid, &xy));
+        let boundary = [0, 1, 2, 3, 4, 9, 14, 13, 12, 11, 10, 5];
+        let mut roots = draw_with_outer_face(&embedding, &boundary).realizer.roots;
+        roots.sort();
+        assert_ne!(roots, [0, 1, 5]);
+        assert!(roots.iter().all(|v| boundary.contains(v)));
+
+        // the square becomes K4 with the chord 0 - 3 added in the outer face, so 1 ends up inside
+        let grid = generate_grid_graph(2, 2);
+        let xy = [(0, 0), (1, 0), (0, -1), (1, -1)];
+        let embedding = embed_with_rotation(&grid, &rotation_of_points(&grid, &xy));
+        let drawing = draw_with_outer_face(&embedding, &[0, 1, 3, 2]);
+        assert_eq!(drawing.coordinates, vec![(1, 0), (1, 1), (0, 2), (2, 1)]);
+        assert_eq!(drawing.realizer.roots, [0, 3, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Boundary should be a face of the embedding")]
+    fn test_draw_with_outer_face_not_a_face() {
+        use crate::testing::grids::generate_grid_graph;
+        use crate::types::embed_with_rotation;
+
+        let grid = generate_grid_graph(3, 3);
+        let xy: Vec<(i64, i64)> = (0..9).map(|v| (v % 3, -(v / 3))).collect();
+        let embedding = embed_with_rotation(&grid, &rotation_of_points(&grid, &xy));
+        // vertices of the face 0, 1, 4, 3 in a wrong order
+        draw_with_outer_face(&embedding, &[0, 4, 1, 3]);
+    }
 }
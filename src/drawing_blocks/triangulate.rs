@@ -1,34 +1,31 @@
 use super::circular_list::CircularList;
 use super::faces::get_faces;
+use crate::collections::HashSet;
 use crate::{
     UnGraph,
     embedding::is_planar,
-    types::{DiGraph, EdgeLabel},
+    input::require_simple,
+    types::{DiGraph, EdgeLabel, undirected_of},
 };
-use petgraph::visit::{EdgeRef, NodeIndexable};
-
-fn to_ungraph(graph: &DiGraph) -> UnGraph {
-    let mut g = UnGraph::new_undirected();
-    for _ in 0..graph.node_count() {
-        g.add_node(0);
-    }
-    for e in graph.edge_references() {
-        let u = e.source();
-        let v = e.target();
-        if u.index() < v.index() {
-            g.add_edge(u, v, EdgeLabel::Real);
-        }
-    }
-    g
-}
+use petgraph::visit::NodeIndexable;
 
 fn do_embed(graph: &mut DiGraph) {
-    let g_un = to_ungraph(graph);
+    let g_un = undirected_of(graph);
     let (_is_planar, embedding) = is_planar(&g_un, false);
     *graph = embedding;
 }
 
+/// Returns a planar embedding of a maximal planar graph containing given planar graph.
+///
+/// The graph is connected, made biconnected and then every face is split into triangles,
+/// adding edges only where needed. Hence an already triangulated graph is returned with
+/// the same edge set (only its embedding may differ), so `triangulate` is idempotent.
+///
+/// Panics if the graph is not simple, see [`require_simple`].
 pub fn triangulate(graph: &UnGraph) -> DiGraph {
+    if let Err(err) = require_simple(graph) {
+        panic!("{}", err);
+    }
     let (_is_planar, mut g) = is_planar(graph, false);
     connect_components(&mut g);
     do_embed(&mut g);
@@ -39,6 +36,96 @@ pub fn triangulate(graph: &UnGraph) -> DiGraph {
     g
 }
 
+/// Returns the embedding of a maximal planar graph containing the graph given by a rotation system.
+///
+/// `rotation[u]` lists neighbors of `u` in the order of the embedding, the same way `neighbors` of an
+/// embedding returned by `is_planar` do. Every face is split into triangles by inserting chords directly
+/// into the rotation, so unlike [`triangulate`] no planarity test is run.
+///
+/// The graph has to be simple and connected, with at least 3 vertices.
+/// Panics if `rotation` is not symmetric or a face cannot be triangulated (the rotation is not planar).
+pub fn triangulate_rotation(rotation: &[Vec<usize>]) -> DiGraph {
+    let n = rotation.len();
+    assert!(n >= 3, "Graph must have at least 3 vertices");
+
+    let mut rotation = rotation.to_vec();
+    let mut adjacent = HashSet::new();
+    for (u, rot) in rotation.iter().enumerate() {
+        for &v in rot {
+            assert!(
+                rotation[v].contains(&u),
+                "Rotation system must be symmetric"
+            );
+            adjacent.insert((u.min(v), u.max(v)));
+        }
+    }
+
+    // face walks of the input rotation, half-edge u -> v is followed by v -> (neighbor after u around v)
+    let mut used = HashSet::new();
+    let mut faces = Vec::new();
+    for u in 0..n {
+        for &v in &rotation[u] {
+            if used.contains(&(u, v)) {
+                continue;
+            }
+            let mut walk = Vec::new();
+            let (mut a, mut b) = (u, v);
+            while used.insert((a, b)) {
+                walk.push(a);
+                let rot = &rotation[b];
+                let pos = rot.iter().position(|&x| x == a).unwrap();
+                (a, b) = (b, rot[(pos + 1) % rot.len()]);
+            }
+            faces.push(walk);
+        }
+    }
+
+    // cut off triangles w[i], w[i + 1], w[i + 2] with chords w[i] - w[i + 2] until the face is a triangle
+    for mut walk in faces {
+        while walk.len() > 3 {
+            let k = walk.len();
+            let i = (0..k)
+                .find(|&i| {
+                    let (a, c) = (walk[i], walk[(i + 2) % k]);
+                    a != c && !adjacent.contains(&(a.min(c), a.max(c)))
+                })
+                .expect("Rotation system must be planar");
+            let (p, a, b, c) = (
+                walk[(i + k - 1) % k],
+                walk[i],
+                walk[(i + 1) % k],
+                walk[(i + 2) % k],
+            );
+
+            // the face lies between p and b around a, and between b and the next vertex around c
+            let pos = rotation[a].iter().position(|&x| x == p).unwrap();
+            rotation[a].insert(pos + 1, c);
+            let pos = rotation[c].iter().position(|&x| x == b).unwrap();
+            rotation[c].insert(pos + 1, a);
+
+            adjacent.insert((a.min(c), a.max(c)));
+            walk.remove((i + 1) % k);
+        }
+    }
+
+    embedding_of_rotation(&rotation)
+}
+
+/// Returns the embedding given by a rotation system, `neighbors(u)` of the result iterates `rotation[u]`.
+pub(crate) fn embedding_of_rotation(rotation: &[Vec<usize>]) -> DiGraph {
+    let mut g = DiGraph::new();
+    for u in 0..rotation.len() {
+        g.add_node(u as u32);
+    }
+    for (u, rot) in rotation.iter().enumerate() {
+        // petgraph iterates edges around a vertex in reversed order of insertion
+        for &v in rot.iter().rev() {
+            g.add_edge(g.from_index(u), g.from_index(v), EdgeLabel::Real);
+        }
+    }
+    g
+}
+
 fn connect_components(g: &mut DiGraph) {
     let n = g.node_count();
     let mut visited = vec![false; n];
@@ -275,4 +362,79 @@ mod tests {
             }
         }
     }
+
+    fn edge_set(g: &DiGraph) -> Vec<(usize, usize)> {
+        let mut edges: Vec<_> = g
+            .edge_references()
+            .map(|e| (e.source().index(), e.target().index()))
+            .filter(|&(u, v)| u < v)
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_triangulation_idempotent() {
+        for n in 3..=5 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: 1 << (n * (n - 1) / 2),
+            };
+
+            for g in enumerator {
+                if !is_planar(&g, false).0 {
+                    continue;
+                }
+
+                let once = triangulate(&g);
+                let twice = triangulate(&undirected_of(&once));
+                assert_eq!(edge_set(&once), edge_set(&twice));
+            }
+        }
+    }
+
+    #[test]
+    fn test_triangulate_rotation_exhaustive() {
+        for n in 3..=6 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: 1 << (n * (n - 1) / 2),
+            };
+
+            for g in enumerator {
+                let (planar, embedding) = is_planar(&g, false);
+                if !planar || petgraph::algo::connected_components(&g) != 1 {
+                    continue;
+                }
+
+                let rotation: Vec<Vec<usize>> = (0..n)
+                    .map(|v| {
+                        embedding
+                            .neighbors(embedding.from_index(v))
+                            .map(|x| x.index())
+                            .collect()
+                    })
+                    .collect();
+                let tri_g = triangulate_rotation(&rotation);
+
+                // original edges are kept and every face of the new rotation is a triangle
+                assert!(
+                    edge_set(&embedding)
+                        .iter()
+                        .all(|e| edge_set(&tri_g).contains(e))
+                );
+                assert!(is_simple(&undirected_of(&tri_g)));
+                assert_eq!(tri_g.edge_count(), 2 * (3 * n - 6));
+                assert!(get_faces(&tri_g).iter().all(|f| f.order.len() == 3));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "parallel edges between 1 and 2")]
+    fn test_triangulate_multigraph() {
+        triangulate(&crate::input::from_str("0,1\n1,2\n2,0\n1,2\n"));
+    }
 }
@@ -1,3 +1,6 @@
+use crate::UnGraph;
+use crate::drawing_blocks::dual_graph::DualGraph;
+use crate::drawing_blocks::geometry::Point;
 use crate::types::DiGraph;
 use petgraph::visit::EdgeRef;
 use petgraph::visit::NodeIndexable;
@@ -267,3 +270,146 @@ pub fn visualize_schnyder(
     writeln!(output, "</svg>").unwrap();
     output
 }
+
+/// Generates an SVG of a plane graph with its dual graph overlaid.
+///
+/// Primal graph is black, dual graph is dashed blue. Every face is drawn as a dual vertex placed at the centroid
+/// of the vertices of the face (for the outer face too, so it lies inside the drawing).
+/// `points` are the coordinates used to compute `dual` (see `get_dual_graph`).
+pub fn visualize_primal_dual(primal: &UnGraph, points: &[Point], dual: &DualGraph) -> String {
+    let mut output = String::new();
+    let size = 1000.0;
+    let padding = 50.0;
+
+    let centroids: Vec<(f64, f64)> = dual
+        .faces
+        .iter()
+        .map(|face| {
+            let k = face.vertices.len().max(1) as f64;
+            let sx: f64 = face.vertices.iter().map(|&v| points[v].x() as f64).sum();
+            let sy: f64 = face.vertices.iter().map(|&v| points[v].y() as f64).sum();
+            (sx / k, sy / k)
+        })
+        .collect();
+
+    // Find bounds
+    let (mut min_x, mut min_y) = (f64::MAX, f64::MAX);
+    let (mut max_x, mut max_y) = (f64::MIN, f64::MIN);
+    for p in points {
+        min_x = min_x.min(p.x() as f64);
+        max_x = max_x.max(p.x() as f64);
+        min_y = min_y.min(p.y() as f64);
+        max_y = max_y.max(p.y() as f64);
+    }
+    let scale = (size - 2.0 * padding) / (max_x - min_x).max(max_y - min_y).max(1.0);
+    let to_svg = |(x, y): (f64, f64)| {
+        (
+            padding + (x - min_x) * scale,
+            size - (padding + (y - min_y) * scale), // Flip Y for SVG
+        )
+    };
+
+    writeln!(
+        output,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\">",
+        size, size
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "  <rect width=\"100%\" height=\"100%\" fill=\"white\" />"
+    )
+    .unwrap();
+
+    // Primal edges
+    for e in primal.edge_references() {
+        let p = &points[e.source().index()];
+        let q = &points[e.target().index()];
+        let (x1, y1) = to_svg((p.x() as f64, p.y() as f64));
+        let (x2, y2) = to_svg((q.x() as f64, q.y() as f64));
+        writeln!(
+            output,
+            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" stroke-width=\"2\" />",
+            x1, y1, x2, y2
+        )
+        .unwrap();
+    }
+
+    // Dual edges
+    for e in dual.graph.edge_references() {
+        let (x1, y1) = to_svg(centroids[e.source().index()]);
+        let (x2, y2) = to_svg(centroids[e.target().index()]);
+        writeln!(
+            output,
+            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"blue\" stroke-width=\"2\" stroke-dasharray=\"6,4\" />",
+            x1, y1, x2, y2
+        )
+        .unwrap();
+    }
+
+    // Primal vertices
+    for p in points {
+        let (x, y) = to_svg((p.x() as f64, p.y() as f64));
+        writeln!(
+            output,
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"black\" />",
+            x, y
+        )
+        .unwrap();
+    }
+
+    // Dual vertices
+    for &c in &centroids {
+        let (x, y) = to_svg(c);
+        writeln!(
+            output,
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"blue\" />",
+            x, y
+        )
+        .unwrap();
+    }
+
+    writeln!(output, "</svg>").unwrap();
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing_blocks::dual_graph::get_dual_graph;
+    use crate::testing::grids::{generate_grid_graph, get_arbitrary_embedding_of_grid};
+
+    #[test]
+    fn test_visualize_primal_dual_grid() {
+        let graph = generate_grid_graph(3, 4);
+        let points = get_arbitrary_embedding_of_grid(3, 4);
+        let dual = get_dual_graph(&points, &graph);
+        let svg = visualize_primal_dual(&graph, &points, &dual);
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("fill=\"black\" />").count(), graph.node_count());
+        assert_eq!(
+            svg.matches("stroke-dasharray").count(),
+            dual.graph.edge_count()
+        );
+
+        // one dual vertex per face, all inside the drawing of the grid
+        let dual_vertices: Vec<(f64, f64)> = svg
+            .lines()
+            .filter(|l| l.contains("fill=\"blue\""))
+            .map(|l| {
+                let attr = |name: &str| -> f64 {
+                    let start = l.find(&format!("{}=\"", name)).unwrap() + name.len() + 2;
+                    let end = start + l[start..].find('"').unwrap();
+                    l[start..end].parse().unwrap()
+                };
+                (attr("cx"), attr("cy"))
+            })
+            .collect();
+        assert_eq!(dual_vertices.len(), dual.faces.len());
+        for (x, y) in dual_vertices {
+            assert!((50.0..=950.0).contains(&x));
+            assert!((50.0..=950.0).contains(&y));
+        }
+    }
+}
@@ -1,7 +1,9 @@
-use petgraph::visit::EdgeRef;
+use crate::collections::HashMap;
+use petgraph::visit::{EdgeRef, NodeIndexable};
 
 use crate::{
-    UnGraph,
+    EdgeLabel, UnGraph,
+    drawing_blocks::faces::get_faces,
     embedding_blocks::{
         acceptable_adj::make_adjacency_lists_acceptable,
         embed::embed_graph,
@@ -17,44 +19,354 @@ use crate::{
 ///
 /// Returns a tuple where the first element is a boolean indicating whether the graph is planar, and the second element is either a planar embedding of the graph of it's corresponding kuratowski subgraph if the graph is not planar.
 ///
+/// If you test many graphs, consider [`PlanarityTester`] which reuses its buffers between calls.
+///
 /// Reference:
 /// [The Left-Right Planarity Test](https://acm.math.spbu.ru/~sk1/download/papers/planar//brandes2010-planarity.pdf)
 pub fn is_planar(graph: &UnGraph, with_counterexample: bool) -> (bool, DiGraph) {
-    let n = graph.node_count();
-    let m = graph.edge_count();
+    PlanarityTester::new().run(graph, with_counterexample)
+}
 
-    let mut g = GraphInternal::new(n, m);
-    for e in graph.edge_references() {
-        let u = e.source();
-        let v = e.target();
-        g.add_edge(u.index(), v.index());
+/// Same as `is_planar(graph, false).0`, but doesn't build the embedding.
+///
+/// Use it when only the yes/no answer is needed, e.g. when checking many candidate edges.
+pub fn is_planar_bool(graph: &UnGraph) -> bool {
+    PlanarityTester::new().test_bool(graph)
+}
+
+/// Returns `true` iff the vertex pairs `e1` and `e2` can both be drawn inside a common face of `embedding`
+/// without crossing each other.
+///
+/// `embedding` is a planar embedding as returned by [`is_planar`], pairs are internal indices
+/// and may or may not be edges of the embedding already. They can be drawn inside a face if
+/// all endpoints lie on its boundary and the pairs don't interleave along it.
+/// Pairs sharing an endpoint never interleave.
+///
+/// A face walk may visit a vertex more than once (e.g. a cut vertex), every visit is a separate corner
+/// of the face, so all choices of corners for the endpoints are tried.
+pub fn edges_independent_in_embedding(
+    embedding: &DiGraph,
+    e1: (usize, usize),
+    e2: (usize, usize),
+) -> bool {
+    get_faces(embedding).iter().any(|face| {
+        let corners = |v: usize| -> Vec<usize> {
+            (0..face.order.len())
+                .filter(|&i| face.order[i] == v)
+                .collect()
+        };
+        let (a, b, c, d) = (corners(e1.0), corners(e1.1), corners(e2.0), corners(e2.1));
+        if a.is_empty() || b.is_empty() || c.is_empty() || d.is_empty() {
+            return false;
+        }
+        // both pairs can start at the same corner of the shared endpoint
+        if [e1.0, e1.1].contains(&e2.0) || [e1.0, e1.1].contains(&e2.1) {
+            return true;
+        }
+
+        a.iter().any(|&a| {
+            b.iter().any(|&b| {
+                let (lo, hi) = (a.min(b), a.max(b));
+                let inside = |x: usize| lo < x && x < hi;
+                c.iter().any(|&c| d.iter().any(|&d| inside(c) == inside(d)))
+            })
+        })
+    })
+}
+
+/// Kind of the Kuratowski obstruction, i.e. which graph a non-planar graph contains a subdivision of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KuratowskiKind {
+    K5,
+    K33,
+}
+
+/// Returns the kind of the Kuratowski subgraph found in the graph, or `None` if the graph is planar.
+///
+/// Branch vertices of a subdivided `K5` have degree `4`, while in a subdivided `K3,3` all degrees are at most `3`.
+pub fn kuratowski_type(graph: &UnGraph) -> Option<KuratowskiKind> {
+    let (planar, counterexample) = is_planar(graph, true);
+    if planar {
+        return None;
     }
 
-    // root the graph, calculate low1, low2, nesting_depth, parent and height
-    let mut roots = vec![];
-    for u in 0..n {
-        if g.height[u] == usize::MAX {
-            roots.push(u);
-            g.height[u] = 0;
-            dfs1(&mut g, u);
+    // the counterexample stores every edge in both directions
+    if counterexample
+        .node_indices()
+        .any(|v| counterexample.neighbors(v).count() == 4)
+    {
+        Some(KuratowskiKind::K5)
+    } else {
+        Some(KuratowskiKind::K33)
+    }
+}
+
+/// Returns `Some(true)` if the graph has genus at most `1`, as far as we can tell without toroidal embedding.
+///
+/// For now only genus `0` is recognized (via [`is_planar`]), so planar graphs give `Some(true)`
+/// and non-planar graphs give `None` (unknown) until toroidal embedding is implemented.
+/// `Some(false)` is reserved for graphs proven not to embed on the torus.
+pub fn maximum_genus_le_1(graph: &UnGraph) -> Option<bool> {
+    if is_planar_bool(graph) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Planarity tester owning the internal buffers of the LR algorithm.
+///
+/// Buffers are cleared and resized instead of being allocated again for every graph,
+/// which pays off when [`is_planar`] would be called thousands of times, e.g. in exhaustive tests.
+/// `benches/planarity.rs` compares it with [`is_planar`] and [`is_planar_bool`] on many small graphs.
+///
+/// ```rust
+/// use spqr_trees::embedding::PlanarityTester;
+/// use spqr_trees::input::from_str;
+///
+/// let mut tester = PlanarityTester::new();
+/// assert!(tester.test(&from_str("0,1\n1,2\n2,0\n")).0);
+/// ```
+pub struct PlanarityTester {
+    g: GraphInternal,
+    lr_stuff: LrOrientation,
+    roots: Vec<usize>,
+}
+
+impl Default for PlanarityTester {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlanarityTester {
+    pub fn new() -> Self {
+        PlanarityTester {
+            g: GraphInternal::new(0, 0),
+            lr_stuff: LrOrientation::new(0, 0),
+            roots: Vec::new(),
         }
     }
 
-    // sort edges inside adjacency lists according to nesting_depth
-    make_adjacency_lists_acceptable(&mut g);
+    /// Works exactly like [`is_planar`] without a counterexample.
+    pub fn test(&mut self, graph: &UnGraph) -> (bool, DiGraph) {
+        self.run(graph, false)
+    }
+
+    /// Works exactly like [`is_planar_bool`].
+    pub fn test_bool(&mut self, graph: &UnGraph) -> bool {
+        // empty graph is planar, LR machinery needs at least one vertex
+        graph.node_count() == 0 || self.lr_orientation(graph)
+    }
 
-    // calculate LR orientation
-    let mut lr_stuff = LrOrientation::new(n, m);
-    for &u in &roots {
-        if !dfs2(&mut g, &mut lr_stuff, u) {
+    fn run(&mut self, graph: &UnGraph, with_counterexample: bool) -> (bool, DiGraph) {
+        // empty graph is planar, LR machinery needs at least one vertex
+        if graph.node_count() == 0 {
+            return (true, DiGraph::new());
+        }
+
+        // trees are planar with any rotation system, no need for the LR machinery
+        if let Some(embedding) = tree_embedding(graph) {
+            return (true, embedding);
+        }
+
+        if !self.lr_orientation(graph) {
             return (
                 false,
                 get_counterexample(graph.clone(), with_counterexample),
             );
         }
+
+        (
+            true,
+            embed_graph(&mut self.g, &mut self.lr_stuff, &self.roots),
+        )
+    }
+
+    /// Runs the first two phases of the LR algorithm, returns `false` iff the graph is not planar.
+    fn lr_orientation(&mut self, graph: &UnGraph) -> bool {
+        let n = graph.node_count();
+        let m = graph.edge_count();
+
+        let g = &mut self.g;
+        g.reset(n, m);
+        for e in graph.edge_references() {
+            let u = e.source();
+            let v = e.target();
+            g.add_edge(u.index(), v.index());
+        }
+
+        // root the graph, calculate low1, low2, nesting_depth, parent and height
+        let roots = &mut self.roots;
+        roots.clear();
+        for u in 0..n {
+            if g.height[u] == usize::MAX {
+                roots.push(u);
+                g.height[u] = 0;
+                dfs1(g, u);
+            }
+        }
+
+        // sort edges inside adjacency lists according to nesting_depth
+        make_adjacency_lists_acceptable(g);
+
+        // calculate LR orientation
+        let lr_stuff = &mut self.lr_stuff;
+        lr_stuff.reset(n, m);
+        roots.iter().all(|&u| dfs2(g, lr_stuff, u))
+    }
+}
+
+/// Returns the embedding of a tree in which neighbors of every vertex are in increasing order of indices,
+/// or `None` if the graph is not a tree.
+fn tree_embedding(graph: &UnGraph) -> Option<DiGraph> {
+    let n = graph.node_count();
+    if n == 0 || graph.edge_count() != n - 1 {
+        return None;
+    }
+
+    let mut neighbors = vec![Vec::new(); n];
+    for e in graph.edge_references() {
+        let (u, v) = (e.source().index(), e.target().index());
+        neighbors[u].push(v);
+        neighbors[v].push(u);
+    }
+
+    // connected with n - 1 edges <=> tree
+    let mut visited = vec![false; n];
+    let mut stack = vec![0];
+    visited[0] = true;
+    let mut visited_count = 1;
+    while let Some(u) = stack.pop() {
+        for &v in &neighbors[u] {
+            if !visited[v] {
+                visited[v] = true;
+                visited_count += 1;
+                stack.push(v);
+            }
+        }
+    }
+    if visited_count != n {
+        return None;
+    }
+
+    let mut embedding = DiGraph::new();
+    for u in 0..n {
+        embedding.add_node(u as u32);
+    }
+    for (u, rot) in neighbors.iter_mut().enumerate() {
+        rot.sort();
+        // petgraph iterates edges around a vertex in reversed order of insertion
+        for &v in rot.iter().rev() {
+            embedding.add_edge(
+                embedding.from_index(u),
+                embedding.from_index(v),
+                EdgeLabel::Real,
+            );
+        }
+    }
+
+    Some(embedding)
+}
+
+/// Planar embedding stored as a doubly connected edge list (DCEL).
+///
+/// Every edge `{u, v}` of the graph is split into two half-edges `u -> v` and `v -> u`,
+/// which are each other's `twin`. Half-edges are numbered from `0` to `2m - 1`.
+///
+/// - `origin[e]`: Vertex the half-edge `e` starts at.
+/// - `twin[e]`: Half-edge going in the opposite direction.
+/// - `next[e]`: Half-edge following `e` on the boundary of its face.
+/// - `face[e]`: Face on the boundary of which `e` lies, faces are numbered from `0` to `face_count - 1`.
+#[derive(Debug, Clone)]
+pub struct Dcel {
+    pub origin: Vec<usize>,
+    pub twin: Vec<usize>,
+    pub next: Vec<usize>,
+    pub face: Vec<usize>,
+    pub face_count: usize,
+}
+
+impl Dcel {
+    /// Returns the vertex the half-edge `e` ends at.
+    pub fn target(&self, e: usize) -> usize {
+        self.origin[self.twin[e]]
+    }
+}
+
+/// Returns a planar embedding of the graph as a DCEL or `None` if the graph is not planar.
+///
+/// It is purely combinatorial, so faces (and the dual graph) can be obtained without coordinates.
+/// The embedding is the one computed by [`is_planar`].
+///
+/// Parallel edges are supported, self-loops are ignored.
+pub fn planar_dcel(graph: &UnGraph) -> Option<Dcel> {
+    let (planar, embedding) = is_planar(graph, false);
+    if !planar {
+        return None;
+    }
+
+    let n = embedding.node_count();
+    let half_edges = embedding.edge_count();
+
+    // rotation system, half-edges around each vertex
+    let mut rotation = vec![Vec::new(); n];
+    let mut origin = vec![0; half_edges];
+    let mut between: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (u, rot) in rotation.iter_mut().enumerate() {
+        for e in embedding.edges(embedding.from_index(u)) {
+            let (eid, v) = (e.id().index(), e.target().index());
+            rot.push(eid);
+            origin[eid] = u;
+            between.entry((u, v)).or_default().push(eid);
+        }
+    }
+
+    // parallel edges are consecutive around both endpoints, but in opposite orders
+    let mut twin = vec![0; half_edges];
+    for (&(u, v), uv) in between.iter() {
+        let vu = &between[&(v, u)];
+        for (k, &eid) in uv.iter().enumerate() {
+            twin[eid] = vu[vu.len() - 1 - k];
+        }
+    }
+
+    let mut position = vec![0; half_edges];
+    for rot in rotation.iter() {
+        for (i, &eid) in rot.iter().enumerate() {
+            position[eid] = i;
+        }
+    }
+
+    let mut next = vec![0; half_edges];
+    for eid in 0..half_edges {
+        let t = twin[eid];
+        let v = origin[t];
+        next[eid] = rotation[v][(position[t] + 1) % rotation[v].len()];
+    }
+
+    let mut face = vec![usize::MAX; half_edges];
+    let mut face_count = 0;
+    for eid in 0..half_edges {
+        if face[eid] != usize::MAX {
+            continue;
+        }
+
+        let mut curr = eid;
+        while face[curr] == usize::MAX {
+            face[curr] = face_count;
+            curr = next[curr];
+        }
+        face_count += 1;
     }
 
-    (true, embed_graph(&mut g, &mut lr_stuff, &roots))
+    Some(Dcel {
+        origin,
+        twin,
+        next,
+        face,
+        face_count,
+    })
 }
 
 #[cfg(test)]
@@ -97,6 +409,251 @@ mod tests {
         }
     }
     
+    fn verify_dcel(graph: &UnGraph, dcel: &Dcel) {
+        let m = graph
+            .edge_references()
+            .filter(|e| e.source() != e.target())
+            .count();
+        assert_eq!(dcel.origin.len(), 2 * m);
+
+        for e in 0..2 * m {
+            assert_ne!(dcel.twin[e], e);
+            assert_eq!(dcel.twin[dcel.twin[e]], e);
+            assert_eq!(dcel.origin[dcel.next[e]], dcel.target(e));
+            assert_eq!(dcel.face[dcel.next[e]], dcel.face[e]);
+
+            // following `next` returns to the start
+            let mut curr = dcel.next[e];
+            let mut steps = 1;
+            while curr != e {
+                curr = dcel.next[curr];
+                steps += 1;
+                assert!(steps <= 2 * m);
+            }
+        }
+
+        // Euler's formula for a connected plane graph
+        let n = graph.node_count();
+        assert_eq!(n + dcel.face_count, m + 2);
+    }
+
+    #[test]
+    fn test_maximum_genus_le_1() {
+        let grid = crate::testing::grids::generate_grid_graph(4, 5);
+        assert_eq!(maximum_genus_le_1(&grid), Some(true));
+
+        let k5 = crate::input::from_str("0,1\n0,2\n0,3\n0,4\n1,2\n1,3\n1,4\n2,3\n2,4\n3,4\n");
+        assert_eq!(maximum_genus_le_1(&k5), None);
+    }
+
+    #[test]
+    fn test_edges_independent_in_embedding() {
+        let square = crate::input::from_str("0,1\n1,2\n2,3\n3,0\n");
+        let (planar, embedding) = is_planar(&square, false);
+        assert!(planar);
+
+        // diagonals interleave on both faces
+        assert!(!edges_independent_in_embedding(&embedding, (0, 2), (1, 3)));
+        // boundary edges share both faces
+        assert!(edges_independent_in_embedding(&embedding, (0, 1), (2, 3)));
+        assert!(edges_independent_in_embedding(&embedding, (0, 1), (1, 2)));
+        // a diagonal and a boundary edge
+        assert!(edges_independent_in_embedding(&embedding, (0, 2), (2, 3)));
+
+        // wheel with hub 4, edges 4 - 0 and 1 - 2 don't lie on a common face
+        let wheel = crate::input::from_str("0,1\n1,2\n2,3\n3,0\n0,4\n1,4\n2,4\n3,4\n");
+        let (_, embedding) = is_planar(&wheel, false);
+        assert!(!edges_independent_in_embedding(&embedding, (4, 0), (1, 2)));
+        assert!(edges_independent_in_embedding(&embedding, (4, 0), (0, 1)));
+    }
+
+    #[test]
+    fn test_edges_independent_in_embedding_repeated_vertex() {
+        // two pentagons sharing vertex 0, one face walk visits 0 twice
+        let graph = crate::input::from_str("0,1\n1,2\n2,3\n3,4\n4,0\n0,5\n5,6\n6,7\n7,8\n8,0\n");
+        let (planar, embedding) = is_planar(&graph, false);
+        assert!(planar);
+
+        let mut walk = get_faces(&embedding)
+            .into_iter()
+            .map(|face| face.order)
+            .find(|order| order.iter().filter(|&&v| v == 0).count() == 2)
+            .unwrap();
+        let first = walk.iter().position(|&v| v == 0).unwrap();
+        walk.rotate_left(first);
+        // walk is 0 x1 x2 x3 x4 0 y1 y2 y3 y4, x's are vertices of one pentagon and y's of the other
+        assert_eq!(walk.len(), 10);
+        assert_eq!(walk[5], 0);
+        let (x1, x2, x3, y2) = (walk[1], walk[2], walk[3], walk[7]);
+
+        // 0 - x3 is drawn from the second visit of 0, on the same side of x2 - y2 as x3
+        assert!(edges_independent_in_embedding(
+            &embedding,
+            (x2, y2),
+            (0, x3)
+        ));
+        assert!(edges_independent_in_embedding(
+            &embedding,
+            (0, x3),
+            (x2, y2)
+        ));
+        // x1 and x3 are on different sides of x2 - y2
+        assert!(!edges_independent_in_embedding(
+            &embedding,
+            (x2, y2),
+            (x1, x3)
+        ));
+    }
+
+    #[test]
+    fn test_kuratowski_type() {
+        use crate::input::from_str;
+
+        let k5 = from_str("0,1\n0,2\n0,3\n0,4\n1,2\n1,3\n1,4\n2,3\n2,4\n3,4\n");
+        assert_eq!(kuratowski_type(&k5), Some(KuratowskiKind::K5));
+
+        let k33 = from_str("0,3\n0,4\n0,5\n1,3\n1,4\n1,5\n2,3\n2,4\n2,5\n");
+        assert_eq!(kuratowski_type(&k33), Some(KuratowskiKind::K33));
+
+        // Petersen graph contains only K3,3 subdivisions, all degrees are 3
+        let petersen =
+            from_str("0,1\n1,2\n2,3\n3,4\n4,0\n0,5\n1,6\n2,7\n3,8\n4,9\n5,7\n7,9\n9,6\n6,8\n8,5\n");
+        assert_eq!(kuratowski_type(&petersen), Some(KuratowskiKind::K33));
+
+        let envelope = crate::input::from_file("assets/envelope.in");
+        assert_eq!(kuratowski_type(&envelope), None);
+    }
+
+    #[test]
+    fn test_is_planar_empty_graph() {
+        let (planar, embedding) = is_planar(&UnGraph::new_undirected(), true);
+        assert!(planar);
+        assert_eq!(embedding.node_count(), 0);
+    }
+
+    #[test]
+    fn test_planar_dcel_envelope() {
+        let graph = crate::input::from_file("assets/envelope.in");
+        let dcel = planar_dcel(&graph).unwrap();
+        verify_dcel(&graph, &dcel);
+        // Euler's formula: 12 - 8 + 2 faces
+        assert_eq!(dcel.face_count, 6);
+    }
+
+    #[test]
+    fn test_planar_dcel_bond() {
+        let graph = crate::input::from_str("0,1\n0,1\n0,1\n1,2\n2,0\n");
+        let dcel = planar_dcel(&graph).unwrap();
+        verify_dcel(&graph, &dcel);
+        assert_eq!(dcel.face_count, 4);
+    }
+
+    #[test]
+    fn test_planar_dcel_non_planar() {
+        let mut k5 = String::new();
+        for u in 0..5 {
+            for v in u + 1..5 {
+                k5.push_str(&format!("{},{}\n", u, v));
+            }
+        }
+        let graph = crate::input::from_str(&k5);
+        assert!(planar_dcel(&graph).is_none());
+    }
+
+    #[test]
+    fn test_planar_dcel_random() {
+        use crate::testing::random_graphs::random_connected_graph;
+
+        for i in 0..100 {
+            let n = 2 + i / 10;
+            let m: usize = 1 + i / 4;
+
+            let graph = random_connected_graph(n, m.max(n - 1), i);
+            if let Some(dcel) = planar_dcel(&graph) {
+                verify_dcel(&graph, &dcel);
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_planar_tree() {
+        use crate::testing::random_graphs::random_tree;
+
+        for i in 0..50 {
+            let tree = random_tree(2 + i, i);
+            let (planar, embedding) = is_planar(&tree, false);
+            assert!(planar);
+            assert_eq!(embedding.edge_count(), 2 * tree.edge_count());
+
+            // canonical rotation: neighbors in increasing order
+            for v in embedding.node_indices() {
+                let rotation: Vec<usize> = embedding.neighbors(v).map(|x| x.index()).collect();
+                assert!(rotation.is_sorted());
+            }
+
+            // a tree has only the outer face
+            let dcel = planar_dcel(&tree).unwrap();
+            verify_dcel(&tree, &dcel);
+            assert_eq!(dcel.face_count, 1);
+        }
+    }
+
+    #[test]
+    fn test_is_planar_bool_matches_is_planar() {
+        use crate::testing::graph_enumerator::GraphEnumeratorState;
+
+        assert!(is_planar_bool(&UnGraph::new_undirected()));
+        for n in 1..=6 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: (1 << (n * (n - 1) / 2)),
+            };
+            for graph in enumerator {
+                assert_eq!(is_planar_bool(&graph), is_planar(&graph, false).0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_planarity_tester_matches_is_planar() {
+        use crate::testing::graph_enumerator::GraphEnumeratorState;
+        use crate::testing::random_graphs::random_connected_graph;
+
+        fn edges(g: &DiGraph) -> Vec<(usize, usize)> {
+            g.edge_references()
+                .map(|e| (e.source().index(), e.target().index()))
+                .collect()
+        }
+
+        let mut tester = PlanarityTester::new();
+        let mut check = |graph: &UnGraph| {
+            let (expected_planar, expected) = is_planar(graph, false);
+            let (planar, embedding) = tester.test(graph);
+            assert_eq!(planar, expected_planar);
+            assert_eq!(embedding.node_count(), expected.node_count());
+            assert_eq!(edges(&embedding), edges(&expected));
+        };
+
+        // sizes go up and down, so buffers are both grown and shrunk
+        for i in 0..200 {
+            let n = 2 + (i * 7) % 20;
+            let m = n - 1 + (i * 13) % (2 * n);
+            check(&random_connected_graph(n, m, i));
+        }
+
+        for n in 2..=5 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: (1 << (n * (n - 1) / 2)),
+            };
+            for graph in enumerator {
+                check(&graph);
+            }
+        }
+    }
+
     #[cfg(all(test, not(debug_assertions)))]
     #[test]
     #[allow(unreachable_code)]
@@ -1,6 +1,6 @@
 use std::collections::BTreeSet;
 
-use crate::{EdgeLabel, UnGraph, embedding::is_planar, types::DiGraph};
+use crate::{EdgeLabel, UnGraph, embedding::is_planar_bool, types::DiGraph};
 use petgraph::algo::is_isomorphic;
 
 /// Given a non-planar graph, extract it's corresponding kuratowski subgraph. Works in O(n^2)
@@ -28,14 +28,14 @@ pub fn get_counterexample(mut graph: UnGraph, with_counterexample: bool) -> DiGr
             graph_test.add_edge(ret_u, ret_v, EdgeLabel::Real);
         }
 
-        if is_planar(&graph_test, false).0 {
+        if is_planar_bool(&graph_test) {
             ret.add_edge(u, v, EdgeLabel::Real);
             ret.add_edge(v, u, EdgeLabel::Real);
             ret_undir.add_edge(u, v, EdgeLabel::Real);
         }
     }
 
-    assert!(!is_planar(&ret_undir, false).0);
+    assert!(!is_planar_bool(&ret_undir));
 
     // it's obvious that we only take edges that are part of the input graph.
 
@@ -66,7 +66,7 @@ pub fn get_counterexample(mut graph: UnGraph, with_counterexample: bool) -> DiGr
         ret_undir.remove_node(node);
     }
 
-    let k_5 = UnGraph::from_edges(&[
+    let k_5 = UnGraph::from_edges([
         (0, 1, EdgeLabel::Real),
         (0, 2, EdgeLabel::Real),
         (0, 3, EdgeLabel::Real),
@@ -78,7 +78,7 @@ pub fn get_counterexample(mut graph: UnGraph, with_counterexample: bool) -> DiGr
         (2, 4, EdgeLabel::Real),
         (3, 4, EdgeLabel::Real),
     ]);
-    let k_33 = UnGraph::from_edges(&[
+    let k_33 = UnGraph::from_edges([
         (0, 3, EdgeLabel::Real),
         (0, 4, EdgeLabel::Real),
         (0, 5, EdgeLabel::Real),
@@ -1,4 +1,10 @@
-use hashbrown::HashMap;
+use crate::collections::HashMap;
+
+/// Sets `v` to `len` copies of `val` without freeing its memory.
+fn reset_vec<T: Clone>(v: &mut Vec<T>, len: usize, val: T) {
+    v.clear();
+    v.resize(len, val);
+}
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Interval {
@@ -70,23 +76,41 @@ pub struct GraphInternal {
     pub parent: Vec<Option<usize>>, // parent edge of each vertex in the DFS tree
     pub height: Vec<usize>,         // height of the vertex in the DFS tree
 
-    pub edge_counts: HashMap<(usize, usize), usize>, // count of edges between pairs of vertices
+    pub(crate) edge_counts: HashMap<(usize, usize), usize>, // count of edges between pairs of vertices
 }
 
 impl GraphInternal {
     pub fn new(n: usize, m: usize) -> Self {
-        GraphInternal {
+        let mut g = GraphInternal {
             n,
             m,
-            adj: vec![Vec::new(); n],
-            edges: Vec::with_capacity(m),
-            low1: vec![usize::MAX; m],
-            low2: vec![usize::MAX; m],
-            nesting_depth: vec![isize::MAX; m],
-            parent: vec![None; n],
-            height: vec![usize::MAX; n],
+            adj: Vec::new(),
+            edges: Vec::new(),
+            low1: Vec::new(),
+            low2: Vec::new(),
+            nesting_depth: Vec::new(),
+            parent: Vec::new(),
+            height: Vec::new(),
             edge_counts: HashMap::new(),
-        }
+        };
+        g.reset(n, m);
+        g
+    }
+
+    /// Brings the structure to the state of `GraphInternal::new(n, m)`, reusing allocated memory.
+    pub fn reset(&mut self, n: usize, m: usize) {
+        self.n = n;
+        self.m = m;
+        self.adj.iter_mut().for_each(Vec::clear);
+        self.adj.resize(n, Vec::new());
+        self.edges.clear();
+        self.edges.reserve(m);
+        reset_vec(&mut self.low1, m, usize::MAX);
+        reset_vec(&mut self.low2, m, usize::MAX);
+        reset_vec(&mut self.nesting_depth, m, isize::MAX);
+        reset_vec(&mut self.parent, n, None);
+        reset_vec(&mut self.height, n, usize::MAX);
+        self.edge_counts.clear();
     }
 
     pub fn get_other_vertex(&self, eid: usize, u: usize) -> usize {
@@ -121,14 +145,25 @@ pub struct LrOrientation {
     pub side: Vec<i8>,
 }
 impl LrOrientation {
-    pub fn new(_: usize, m: usize) -> Self {
-        LrOrientation {
-            stack_bottom: vec![None; m],
-            lowpt_edge: vec![0; m],
-            ref_edge: vec![usize::MAX; m],
+    pub fn new(n: usize, m: usize) -> Self {
+        let mut lr_stuff = LrOrientation {
+            stack_bottom: Vec::new(),
+            lowpt_edge: Vec::new(),
+            ref_edge: Vec::new(),
             stack: Vec::new(),
-            side: vec![1; m], // +1 for right
-        }
+            side: Vec::new(),
+        };
+        lr_stuff.reset(n, m);
+        lr_stuff
+    }
+
+    /// Brings the structure to the state of `LrOrientation::new(n, m)`, reusing allocated memory.
+    pub fn reset(&mut self, _: usize, m: usize) {
+        reset_vec(&mut self.stack_bottom, m, None);
+        reset_vec(&mut self.lowpt_edge, m, 0);
+        reset_vec(&mut self.ref_edge, m, usize::MAX);
+        self.stack.clear();
+        reset_vec(&mut self.side, m, 1); // +1 for right
     }
 
     pub fn merge_intervals(&mut self, p: &mut Interval, q: &mut Interval) {
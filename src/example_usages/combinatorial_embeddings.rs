@@ -1,9 +1,9 @@
+use crate::collections::HashMap;
 use crate::{
     UnGraph, block_cut::get_block_cut_tree, spqr_tree::get_spqr_tree,
     triconnected_blocks::outside_structures::ComponentType,
 };
 use embed_doc_image::embed_doc_image;
-use hashbrown::HashMap;
 use petgraph::graph::NodeIndex;
 
 /// Counts the number of all combinatorial embeddings of a given graph in O(V + E).
@@ -24,8 +24,13 @@ use petgraph::graph::NodeIndex;
 ///
 /// This algorithm can give you an idea how to go through all the embeddings and choose appropriate
 /// based on given conditions.
+///
+/// # Overflow
+/// The number of embeddings grows factorially, e.g. a bond with `k` edges alone gives `(k-1)!`.
+/// That is why we count in `u128`: `usize` (64 bits) overflows already for a bond with 22 edges (`21!`),
+/// while `u128` holds up to a bond with 35 edges (`34!`).
 #[allow(dead_code)]
-pub fn count_combinatorial_embeddings(graph: &UnGraph) -> usize {
+pub fn count_combinatorial_embeddings(graph: &UnGraph) -> u128 {
     let bc_tree = get_block_cut_tree(graph);
 
     if bc_tree.block_count == 1 {
@@ -39,7 +44,7 @@ pub fn count_combinatorial_embeddings(graph: &UnGraph) -> usize {
     }
 
     // init with 1 because we are multiplying
-    let mut block_emb = vec![1; bc_tree.block_count];
+    let mut block_emb = vec![1u128; bc_tree.block_count];
     let mut deg_in_bc = vec![1; bc_tree.cut_count];
     let mut deg_in_og = vec![1; bc_tree.cut_count];
     let mut edges_adj_v = vec![vec![1; bc_tree.block_count]; bc_tree.cut_count];
@@ -63,20 +68,20 @@ pub fn count_combinatorial_embeddings(graph: &UnGraph) -> usize {
         }
     }
 
-    let mut embeddings = 1;
+    let mut embeddings: u128 = 1;
     // this part accounts for biconnected components embeddings
     for i in 0..bc_tree.block_count {
         embeddings *= block_emb[i];
         // this part accounts for choosing first edges
         for j in 0..bc_tree.cut_count {
-            embeddings *= edges_adj_v[j][i];
+            embeddings *= edges_adj_v[j][i] as u128;
         }
     }
     // this part accounts for permutation of edges of biconnected component around cut vertex
     // but restricted to not contain interlacing (1 1 1 2 2 1 1 2 2 2 - numbers indicate component of edge)
     for i in 0..bc_tree.cut_count {
         for j in 1..deg_in_bc[i] - 1 {
-            embeddings *= deg_in_og[i] - j;
+            embeddings *= (deg_in_og[i] - j) as u128;
         }
     }
 
@@ -92,25 +97,27 @@ pub fn count_combinatorial_embeddings(graph: &UnGraph) -> usize {
 /// - **P node (bond)** - (k-1)! embeddings, where k is the number of edges in the bond (NOTE: not k!)
 /// - **R node (triconnected component)** - has 2 embeddings (we count also mirror reflection)
 ///
+/// See [`count_combinatorial_embeddings`] for the overflow threshold of the result.
+///
 /// You can try it yourself on the envelope on 8 vertices.
 ///
 /// SPQR tree of this graph:
 ///
 /// ![SQPR_Envelope][spqr_envelope]
 #[embed_doc_image("spqr_envelope", "assets/spqr_tree_envelope.svg")]
-pub fn count_combinatorial_embeddings_biconnected(graph: &UnGraph) -> usize {
+pub fn count_combinatorial_embeddings_biconnected(graph: &UnGraph) -> u128 {
     if graph.node_count() <= 1 {
         return 1;
     }
 
     let spqr_tree = get_spqr_tree(graph);
-    let mut embeddings = 1;
+    let mut embeddings: u128 = 1;
 
     for component in &spqr_tree.blocks.comp {
         match component.comp_type {
             ComponentType::P => {
-                let k = component.edges.len();
-                embeddings *= (1..k).product::<usize>();
+                let k = component.edges.len() as u128;
+                embeddings *= (1..k).product::<u128>();
             }
             ComponentType::R => {
                 embeddings *= 2;
@@ -127,6 +134,12 @@ mod tests {
     use super::*;
     use crate::EdgeLabel;
 
+    #[test]
+    fn test_count_combinatorial_embeddings_empty_graph() {
+        let graph = UnGraph::new_undirected();
+        assert_eq!(count_combinatorial_embeddings(&graph), 1);
+    }
+
     #[test]
     fn test_count_combinatorial_embeddings_biconnected_single_vertex() {
         let mut graph = UnGraph::new_undirected();
@@ -221,6 +234,24 @@ mod tests {
         assert_eq!(embeddings, 16);
     }
 
+    #[test]
+    fn test_count_combinatorial_embeddings_biconnected_large_bond() {
+        // 25 parallel edges give 24! embeddings, which doesn't fit in 64 bits
+        let mut graph = UnGraph::new_undirected();
+        graph.add_node(0);
+        graph.add_node(1);
+        for _ in 0..25 {
+            graph.add_edge(0.into(), 1.into(), EdgeLabel::Real);
+        }
+
+        let expected = (1..25).product::<u128>();
+        assert!(expected > usize::MAX as u128);
+
+        let embeddings = count_combinatorial_embeddings_biconnected(&graph);
+        assert_eq!(embeddings, expected);
+        assert_eq!(count_combinatorial_embeddings(&graph), expected);
+    }
+
     #[test]
     fn test_count_combinatorial_embeddings_single_edge() {
         let mut graph = UnGraph::new_undirected();
@@ -296,13 +327,13 @@ mod tests {
     #[test]
     fn test_count_combinatorial_embeddings_random_tree() {
         // for tree this is simple: for all v, product((deg(v)-1)!)
-        fn brute_embeddings_tree(tree: &UnGraph) -> usize {
+        fn brute_embeddings_tree(tree: &UnGraph) -> u128 {
             if tree.node_count() <= 1 {
                 return 1;
             }
             let mut embeddings = 1;
             for node in tree.node_indices() {
-                embeddings *= (1..tree.neighbors(node).count()).product::<usize>();
+                embeddings *= (1..tree.neighbors(node).count() as u128).product::<u128>();
             }
             embeddings
         }
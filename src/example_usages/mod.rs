@@ -1,4 +1,6 @@
 pub mod combinatorial_embeddings;
+pub mod network_reliability;
 pub mod oeip;
 pub mod static_triconnectivity_bicon;
 pub mod static_triconnectivity_full;
+pub mod three_coloring;
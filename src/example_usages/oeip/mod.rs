@@ -1,2 +1,3 @@
-pub mod dual_graph;
 pub mod optimal_block_inserter;
+
+pub use crate::drawing_blocks::dual_graph;
@@ -1,16 +1,26 @@
-use hashbrown::{HashMap, HashSet};
-use petgraph::algo::dijkstra;
+use crate::collections::{HashMap, HashSet};
 use petgraph::graph::NodeIndex;
 use petgraph::visit::IntoNodeReferences;
 
-use crate::embedding::is_planar;
-use crate::example_usages::oeip::dual_graph::get_dual_graph;
-use crate::testing::grids::Point;
+use crate::drawing_blocks::dual_graph::{DualGraph, get_dual_graph};
+use crate::drawing_blocks::geometry::Point;
+use crate::embedding::is_planar_bool;
 use crate::{
     EdgeLabel, UnGraph, spqr_blocks::outside_structures::SPQRTree, spqr_tree::get_spqr_tree,
     triconnected_blocks::outside_structures::ComponentType,
 };
 
+/// Expanded skeleton of an R-node together with its dual graph.
+#[derive(Debug, Clone)]
+struct ExpandedSkeleton {
+    dual_graph: DualGraph,
+    /// Maps vertices of the input graph to vertices of the expanded graph
+    node_to_expanded: HashMap<usize, NodeIndex>,
+}
+
+/// Expanded skeletons by R-node and (sorted) marked virtual edges.
+type SkeletonCache = HashMap<(usize, Option<usize>, Option<usize>), ExpandedSkeleton>;
+
 /// Solves the Optimal Edge Insertion Problem (OEIP) for a given biconnected planar graph.
 ///
 /// ## Statement:
@@ -72,7 +82,7 @@ pub struct OptimalBlockInserter {
 #[allow(dead_code)]
 impl OptimalBlockInserter {
     pub fn new(graph: &UnGraph, points: Vec<Point>) -> Self {
-        assert!(is_planar(graph, false).0, "Graph must be planar");
+        assert!(is_planar_bool(graph), "Graph must be planar");
 
         let tree = get_spqr_tree(&graph);
         let mut component_vertex_set = vec![HashSet::new(); tree.blocks.comp.len()];
@@ -214,7 +224,6 @@ impl OptimalBlockInserter {
                     break;
                 }
             }
-
         }
 
         path
@@ -235,6 +244,31 @@ impl OptimalBlockInserter {
 
     /// Returns the optimal number of crossings when inserting edge (u, v) into graph.
     pub fn oeip(&self, u: usize, v: usize) -> i32 {
+        self.oeip_cached(u, v, &mut HashMap::new())
+    }
+
+    /// Returns the optimal number of crossings for all pairs of vertices, `res[u][v] = oeip(u, v)`.
+    ///
+    /// Expanded skeletons of R-nodes and their dual graphs depend only on the R-node and virtual edges
+    /// leading towards `u` and `v`, so they are built once and shared between all queries.
+    pub fn all_pairs_oeip(&self) -> Vec<Vec<i32>> {
+        let n = self.graph.node_count();
+        let mut cache = HashMap::new();
+
+        let mut upper = vec![vec![0; n]; n];
+        for (u, row) in upper.iter_mut().enumerate() {
+            for (v, crossings) in row.iter_mut().enumerate().skip(u + 1) {
+                *crossings = self.oeip_cached(u, v, &mut cache);
+            }
+        }
+
+        // oeip is symmetric
+        (0..n)
+            .map(|u| (0..n).map(|v| upper[u.min(v)][u.max(v)]).collect())
+            .collect()
+    }
+
+    fn oeip_cached(&self, u: usize, v: usize, cache: &mut SkeletonCache) -> i32 {
         if u == v {
             return 0;
         }
@@ -253,8 +287,80 @@ impl OptimalBlockInserter {
         }
         let mut crossings = 0;
 
+        // Iterate through path
+        for (i, node) in path.iter().enumerate() {
+            if self.tree.blocks.comp[*node].comp_type != ComponentType::R {
+                continue; // if deleted there were problems with prev and next
+            }
+            let mut u_virt_edge = None;
+            let mut v_virt_edge = None;
+
+            if !self.component_vertex_set[*node].contains(&u) {
+                let prev_node = path[i - 1];
+                u_virt_edge = Some(self.pair_of_components_to_virt_edge[&(*node, prev_node)]);
+            }
+
+            if !self.component_vertex_set[*node].contains(&v) {
+                let next_node = path[i + 1];
+                v_virt_edge = Some(self.pair_of_components_to_virt_edge[&(*node, next_node)]);
+            }
+
+            // expansion does not depend on which marked edge leads to which vertex
+            let key = (
+                *node,
+                u_virt_edge.min(v_virt_edge),
+                u_virt_edge.max(v_virt_edge),
+            );
+            let skeleton = cache
+                .entry(key)
+                .or_insert_with(|| self.expand_skeleton(*node, u_virt_edge, v_virt_edge));
+            let dual_graph = &skeleton.dual_graph;
+
+            // Faces the new edge can start from (for src) or end in (for dst)
+            let indices = [u, v];
+            let mut endpoints = vec![];
+            for (i, edge) in [u_virt_edge, v_virt_edge].iter().enumerate() {
+                if edge.is_some() {
+                    // Not present in skeleton
+                    endpoints.push(vec![dual_graph.outer_face]);
+                } else {
+                    let x = skeleton.node_to_expanded[&indices[i]].index();
+                    endpoints.push(
+                        (0..dual_graph.faces.len())
+                            .filter(|&j| dual_graph.faces[j].vertices.contains(&x))
+                            .collect(),
+                    );
+                }
+            }
+
+            let weights = vec![1; dual_graph.edge_faces.len()];
+            let dist = dual_graph.crossing_distances(&endpoints[0], &weights);
+            // faces of the expanded skeleton are all reachable, as its dual graph is connected
+            crossings += endpoints[1]
+                .iter()
+                .filter_map(|&j| dist[j])
+                .min()
+                .expect("Some end face should be reachable") as i32;
+        }
+
+        crossings
+    }
+
+    /// Expands R-node `node` into the graph of all real edges reachable from it
+    /// without passing through the marked virtual edges, and builds its dual graph.
+    fn expand_skeleton(
+        &self,
+        node: usize,
+        u_virt_edge: Option<usize>,
+        v_virt_edge: Option<usize>,
+    ) -> ExpandedSkeleton {
+        let mut marked_edges = vec![false; self.tree.blocks.edges.len()];
+        for eid in [u_virt_edge, v_virt_edge].into_iter().flatten() {
+            marked_edges[eid] = true;
+        }
+
         // Updates list of edges of expanded skeleton graph.
-        fn expand_skeleton(
+        fn collect_edges(
             tree: &SPQRTree,
             edges: &mut Vec<usize>,
             marked_edges: &Vec<bool>,
@@ -274,7 +380,7 @@ impl OptimalBlockInserter {
                 }
                 // We don't want to expand marked virtual edges
                 if !marked_edges[pair_of_components_to_virt_edge[&(u, to)]] {
-                    expand_skeleton(
+                    collect_edges(
                         tree,
                         edges,
                         marked_edges,
@@ -286,93 +392,42 @@ impl OptimalBlockInserter {
             }
         }
 
-        // Iterate through path
-        for (i, node) in path.iter().enumerate() {
-            if self.tree.blocks.comp[*node].comp_type != ComponentType::R {
-                continue; // if deleted there were problems with prev and next
-            }
-            let mut edges = vec![];
-            let mut u_virt_edge = None;
-            let mut v_virt_edge = None;
-            let mut marked_edges = vec![false; self.tree.blocks.edges.len()];
-
-            if !self.component_vertex_set[*node].contains(&u) {
-                let prev_node = path[i - 1];
-                u_virt_edge = Some(self.pair_of_components_to_virt_edge[&(*node, prev_node)]);
-                marked_edges[u_virt_edge.unwrap()] = true;
-            }
-
-            if !self.component_vertex_set[*node].contains(&v) {
-                let next_node = path[i + 1];
-                v_virt_edge = Some(self.pair_of_components_to_virt_edge[&(*node, next_node)]);
-                marked_edges[v_virt_edge.unwrap()] = true;
-            }
-
-            expand_skeleton(
-                &self.tree,
-                &mut edges,
-                &marked_edges,
-                *node,
-                None,
-                &self.pair_of_components_to_virt_edge,
-            );
-
-            let mut expanded_graph = UnGraph::new_undirected();
-            let mut node_to_expanded = HashMap::new();
-            // Construct expanded graph
-            for &eid in edges.iter() {
-                let (a, b) = self.tree.blocks.edges[eid];
-                for turn in [a, b] {
-                    if !node_to_expanded.contains_key(&turn) {
-                        let new_node = expanded_graph.add_node(turn as u32);
-                        node_to_expanded.insert(turn, new_node);
-                    }
+        let mut edges = vec![];
+        collect_edges(
+            &self.tree,
+            &mut edges,
+            &marked_edges,
+            node,
+            None,
+            &self.pair_of_components_to_virt_edge,
+        );
+
+        let mut expanded_graph = UnGraph::new_undirected();
+        let mut node_to_expanded = HashMap::new();
+        // Construct expanded graph
+        for &eid in edges.iter() {
+            let (a, b) = self.tree.blocks.edges[eid];
+            for turn in [a, b] {
+                if !node_to_expanded.contains_key(&turn) {
+                    let new_node = expanded_graph.add_node(turn as u32);
+                    node_to_expanded.insert(turn, new_node);
                 }
-                expanded_graph.add_edge(
-                    node_to_expanded[&a],
-                    node_to_expanded[&b],
-                    EdgeLabel::Real,
-                );
-            }
-
-            let mut points = vec![];
-            for id in expanded_graph.node_indices() {
-                let point = self.points[*expanded_graph.node_weight(id).unwrap() as usize];
-                points.push(point);
             }
+            expanded_graph.add_edge(node_to_expanded[&a], node_to_expanded[&b], EdgeLabel::Real);
+        }
 
-            let mut dual_graph = get_dual_graph(&points, &expanded_graph);
+        let mut points = vec![];
+        for id in expanded_graph.node_indices() {
+            let point = self.points[*expanded_graph.node_weight(id).unwrap() as usize];
+            points.push(point);
+        }
 
-            let mut xids = vec![];
-            let indices = vec![u, v];
+        let dual_graph = get_dual_graph(&points, &expanded_graph);
 
-            // Augment dual graph with src and dst
-            for (i, edge) in [u_virt_edge, v_virt_edge].iter().enumerate() {
-                xids.push(dual_graph.graph.add_node(dual_graph.graph.node_count() as u32));
-                if edge.is_some() {
-                    // Not present in skeleton
-                    dual_graph.graph.add_edge(
-                        NodeIndex::new(dual_graph.outer_face),
-                        xids[i],
-                        EdgeLabel::Structure,
-                    );
-                } else {
-                    for (j, face) in dual_graph.faces.iter().enumerate() {
-                        if face.vertices.contains(&node_to_expanded[&indices[i]].index()) {
-                            dual_graph
-                                .graph
-                                .add_edge(NodeIndex::new(j), xids[i], EdgeLabel::Structure);
-                        }
-                    }
-                }
-            }
-
-            // should be BFS but petgraph has dijkstra implemented ;)
-            let costs = dijkstra(&dual_graph.graph, xids[0], Option::from(xids[1]), |_| 1);
-            crossings += costs.get(&xids[1]).unwrap() - 2; // -2  because we added edges to connect to faces
+        ExpandedSkeleton {
+            dual_graph,
+            node_to_expanded,
         }
-
-        crossings
     }
 }
 
@@ -443,6 +498,24 @@ mod tests {
             .min(min_exit_horizontal_2 + min_exit_vertical_1)
     }
 
+    #[test]
+    fn test_all_pairs_oeip() {
+        for (r, c) in [(3, 3), (3, 4), (4, 4)] {
+            let graph = generate_grid_graph(r, c);
+            let points = get_arbitrary_embedding_of_grid(r, c);
+            let block_inserter = OptimalBlockInserter::new(&graph, points);
+
+            let all_pairs = block_inserter.all_pairs_oeip();
+            assert_eq!(all_pairs.len(), r * c);
+            for (u, row) in all_pairs.iter().enumerate() {
+                for (v, &crossings) in row.iter().enumerate() {
+                    assert_eq!(crossings, block_inserter.oeip(u, v));
+                    assert_eq!(crossings, brute_grid_crossings(r, c, u, v));
+                }
+            }
+        }
+    }
+
     #[cfg(all(test, not(debug_assertions)))]
     #[test]
     fn test_oeip() {
@@ -1,4 +1,5 @@
-use hashbrown::HashMap;
+use crate::collections::HashMap;
+use petgraph::visit::NodeIndexable;
 
 use crate::{
     UnGraph, spqr_blocks::outside_structures::RootedSPQRTree, spqr_tree::get_rooted_spqr_tree,
@@ -11,6 +12,10 @@ use crate::{
 ///
 /// Prerequisite: input graph is biconnected
 ///
+/// Edges can be inserted with [`StaticBiconnectedTriconnectivity::insert_edge`]. An edge between the poles of a P-node
+/// or between two non-adjacent vertices of an R-node skeleton is added to that skeleton in place.
+/// Other insertions rebuild the structure from scratch.
+///
 /// ## Reference:
 /// - [On-line maintenance of triconnected components with SPQR-trees](https://link.springer.com/article/10.1007/BF01961541)
 
@@ -19,6 +24,11 @@ pub struct StaticBiconnectedTriconnectivity {
     tree: RootedSPQRTree,
 
     s_links: Vec<HashMap<usize, (Option<usize>, Option<usize>)>>,
+
+    /// Current graph, kept for rebuilding after insertions
+    graph: UnGraph,
+    /// R-nodes and P-nodes whose skeleton contains given vertex
+    rigid_nodes: Vec<Vec<usize>>,
 }
 
 #[allow(dead_code)]
@@ -60,15 +70,90 @@ impl StaticBiconnectedTriconnectivity {
             }
         }
 
+        let mut rigid_nodes = vec![vec![]; graph.node_count()];
+        for (c, comp) in tree.blocks.comp.iter().enumerate() {
+            // a bond with two edges (graph on two vertices) becomes triconnected with the third edge
+            if comp.comp_type == ComponentType::S || comp.edges.len() < 3 {
+                continue;
+            }
+            for &eid in comp.edges.iter() {
+                let (a, b) = tree.blocks.edges[eid];
+                for x in [a, b] {
+                    if rigid_nodes[x].last() != Some(&c) {
+                        rigid_nodes[x].push(c);
+                    }
+                }
+            }
+        }
+        for nodes in rigid_nodes.iter_mut() {
+            nodes.sort();
+            nodes.dedup();
+        }
+
         if tree.blocks.comp.len() > 0 {
             dfs(&tree, 0, &mut mark, &mut s_links);
 
-            StaticBiconnectedTriconnectivity { tree, s_links }
+            StaticBiconnectedTriconnectivity {
+                tree,
+                s_links,
+                graph: graph.clone(),
+                rigid_nodes,
+            }
         } else {
             StaticBiconnectedTriconnectivity {
                 tree,
                 s_links: vec![],
+                graph: graph.clone(),
+                rigid_nodes,
+            }
+        }
+    }
+
+    /// Inserts edge `(u, v)` (internal indices) and updates the structure so that queries stay correct.
+    ///
+    /// Two cases are handled in place, in time proportional to the number of components containing `u`:
+    /// - `u` and `v` are the poles of a P-node, the edge becomes one more edge of the bond,
+    /// - `u` and `v` are non-adjacent vertices of an R-node skeleton, the edge is added to the skeleton,
+    ///   which stays triconnected.
+    ///
+    /// Then no separation pair is destroyed, so the SPQR tree is the one of the new graph and answers do not change.
+    /// Otherwise (e.g. the edge is a chord of an S-node or joins different components) the structure is rebuilt.
+    pub fn insert_edge(&mut self, u: usize, v: usize) {
+        self.graph.add_edge(
+            self.graph.from_index(u),
+            self.graph.from_index(v),
+            crate::EdgeLabel::Real,
+        );
+
+        let blocks = &self.tree.blocks;
+        let joins = |eid: usize| {
+            let (s, t) = blocks.edges[eid];
+            (s, t) == (u, v) || (s, t) == (v, u)
+        };
+        let shared = self.rigid_nodes[u]
+            .iter()
+            .copied()
+            .filter(|c| self.rigid_nodes[v].binary_search(c).is_ok());
+        let target = shared
+            .clone()
+            .find(|&c| blocks.comp[c].comp_type == ComponentType::P)
+            .or_else(|| {
+                shared.clone().find(|&c| {
+                    blocks.comp[c].comp_type == ComponentType::R
+                        && !blocks.comp[c].edges.iter().any(|&eid| joins(eid))
+                })
+            });
+
+        match target {
+            Some(c) => {
+                let blocks = &mut self.tree.blocks;
+                let eid = blocks.edges.len();
+                blocks.edges.push((u, v));
+                blocks.is_real.push(true);
+                blocks.to_split.push(Some(c));
+                blocks.comp[c].edges.push(eid);
             }
+            None => *self = StaticBiconnectedTriconnectivity::new(&self.graph),
         }
     }
 
@@ -99,8 +184,11 @@ impl StaticBiconnectedTriconnectivity {
 
         let proper_a_type = self.tree.blocks.comp[proper_a].comp_type;
 
+        // a bond with only two edges appears only for a graph on two vertices, which is not triconnected
         if proper_a == proper_b
-            && (proper_a_type == ComponentType::R || proper_a_type == ComponentType::P)
+            && (proper_a_type == ComponentType::R
+                || (proper_a_type == ComponentType::P
+                    && self.tree.blocks.comp[proper_a].edges.len() >= 3))
         {
             return true;
         }
@@ -252,6 +340,90 @@ mod tests {
         }
     }
 
+    // types and sizes of components, which are the same for equal SPQR trees
+    fn component_shapes(tree: &RootedSPQRTree) -> Vec<(ComponentType, usize)> {
+        let mut shapes: Vec<_> = tree
+            .blocks
+            .comp
+            .iter()
+            .map(|comp| (comp.comp_type, comp.edges.len()))
+            .collect();
+        shapes.sort_by_key(|&(t, k)| (t.to_string(), k));
+        shapes
+    }
+
+    #[test]
+    fn test_bicon_triconnectivity_insert_edge() {
+        for i in 0..100 {
+            let n = 3 + i / 10;
+            let m: usize = n + i % 10;
+
+            let mut in_graph = random_biconnected_graph(n, m, i);
+            let n = in_graph.node_count();
+            let mut incremental = StaticBiconnectedTriconnectivity::new(&in_graph);
+
+            for j in 0..n {
+                let (u, v) = (j, (j * 7 + i) % n);
+                if u == v {
+                    continue;
+                }
+                incremental.insert_edge(u, v);
+                in_graph.add_edge(
+                    in_graph.from_index(u),
+                    in_graph.from_index(v),
+                    crate::EdgeLabel::Real,
+                );
+                let rebuilt = StaticBiconnectedTriconnectivity::new(&in_graph);
+                assert_eq!(
+                    component_shapes(&incremental.tree),
+                    component_shapes(&rebuilt.tree)
+                );
+
+                for a in 0..n {
+                    for b in 0..n {
+                        assert_eq!(incremental.query(a, b, false), rebuilt.query(a, b, false));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bicon_triconnectivity_insert_edge_in_place() {
+        // wheel with hub 0 and rim 1 - 2 - 3 - 4 is a single R-node, 1 and 3 are not adjacent
+        let wheel = crate::input::from_str("0,1\n0,2\n0,3\n0,4\n1,2\n2,3\n3,4\n4,1\n");
+        // two paths of length 2 and an edge between 0 and 1 form a P-node with two S-nodes
+        let theta = crate::input::from_str("0,1\n0,2\n2,1\n0,3\n3,1\n");
+
+        for (mut graph, (u, v)) in [(wheel, (1, 3)), (theta, (0, 1))] {
+            let mut incremental = StaticBiconnectedTriconnectivity::new(&graph);
+            let before = incremental.tree.clone();
+            incremental.insert_edge(u, v);
+
+            // the edge is appended to a skeleton, nothing else changes
+            let blocks = &incremental.tree.blocks;
+            let eid = blocks.edges.len() - 1;
+            assert_eq!(blocks.edges[..eid], before.blocks.edges[..]);
+            assert_eq!(blocks.edges[eid], (u, v));
+            assert!(blocks.is_real[eid]);
+            let c = blocks.to_split[eid].unwrap();
+            assert_eq!(blocks.comp[c].edges.last(), Some(&eid));
+            assert_eq!(incremental.tree.adj, before.adj);
+            assert_eq!(incremental.tree.alloc_node, before.alloc_node);
+
+            graph.add_edge(
+                graph.from_index(u),
+                graph.from_index(v),
+                crate::EdgeLabel::Real,
+            );
+            let rebuilt = StaticBiconnectedTriconnectivity::new(&graph);
+            assert_eq!(
+                component_shapes(&incremental.tree),
+                component_shapes(&rebuilt.tree)
+            );
+        }
+    }
+
     #[cfg(all(test, not(debug_assertions)))]
     #[test]
     fn test_bicon_triconnectivity() {
@@ -1,4 +1,4 @@
-use hashbrown::HashMap;
+use crate::collections::HashMap;
 use petgraph::visit::{IntoNodeReferences, NodeIndexable};
 
 use crate::{
@@ -13,6 +13,12 @@ use crate::{
 ///
 /// Prerequisite: input graph is connected.
 ///
+/// Vertices can be referred to in two ways:
+/// - [`StaticTriconnectivity::query`] takes internal indices of the input graph (`NodeIndex::index()`),
+/// - [`StaticTriconnectivity::query_labels`] takes node labels (weights) of the input graph.
+///
+/// These coincide only if labels are `0..n-1` in order, which is not true e.g. for blocks extracted from a bigger graph.
+///
 /// ## Reference:
 /// - [On-line maintenance of triconnected components with SPQR-trees](https://link.springer.com/article/10.1007/BF01961541)
 
@@ -24,6 +30,8 @@ pub struct StaticTriconnectivity {
     vertex_numbers_mapping: Vec<HashMap<usize, usize>>, // vertices inside the spqr trees are numbered from 0 to m-1, so here
     // we map the original vertex numbers to the new ones
     parent: Vec<Option<usize>>, // for each vertex in the bct we store its parent
+    labels: Vec<u32>,           // label of each vertex of the input graph
+    label_to_index: HashMap<u32, usize>,
 }
 
 #[allow(dead_code)]
@@ -60,15 +68,22 @@ impl StaticTriconnectivity {
 
         dfs(&bct, 0, &mut parent);
 
+        let labels: Vec<u32> = graph.node_references().map(|(_, &l)| l).collect();
+        let label_to_index = labels.iter().enumerate().map(|(i, &l)| (l, i)).collect();
+
         StaticTriconnectivity {
             tree: bct,
             triconnectivity_blocks,
             vertex_numbers_mapping,
             parent,
+            labels,
+            label_to_index,
         }
     }
 
     fn check_block(&self, block_id: usize, a: usize, b: usize) -> bool {
+        // blocks keep labels, not internal indices
+        let (a, b) = (self.labels[a] as usize, self.labels[b] as usize);
         if let Some(a_inside) = self.vertex_numbers_mapping[block_id].get(&a) {
             if let Some(b_inside) = self.vertex_numbers_mapping[block_id].get(&b) {
                 return self.triconnectivity_blocks[block_id].query(*a_inside, *b_inside, false);
@@ -77,7 +92,16 @@ impl StaticTriconnectivity {
         false
     }
 
-    /// Returns true iff the vertices `a` and `b` are in the same triconnected component.
+    /// Returns true iff the vertices labeled `a` and `b` are in the same triconnected component.
+    ///
+    /// Panics if there is no vertex with given label.
+    pub fn query_labels(&self, a: u32, b: u32) -> bool {
+        let a = *self.label_to_index.get(&a).expect("Label should exist");
+        let b = *self.label_to_index.get(&b).expect("Label should exist");
+        self.query(a, b, false)
+    }
+
+    /// Returns true iff the vertices with internal indices `a` and `b` are in the same triconnected component.
     pub fn query(&self, a: usize, b: usize, rep: bool) -> bool {
         if a == b {
             return true; // trivial case
@@ -186,6 +210,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_triconnectivity_labels() {
+        for i in 0..50 {
+            let n = 2 + i / 5;
+            let m: usize = 1 + i;
+
+            // relabel vertices to non-contiguous labels, in reversed order
+            let mut in_graph = random_connected_graph(n, m, i);
+            for (j, w) in in_graph.node_weights_mut().enumerate() {
+                *w = 10 * (n - j) as u32 + 7;
+            }
+
+            let fast_triconnectivity = StaticTriconnectivity::new(&in_graph);
+            let slow_triconnectivity = StaticTriconnectivityBrute::new(&in_graph);
+
+            for u in in_graph.node_indices() {
+                for v in in_graph.node_indices() {
+                    let expected = slow_triconnectivity.query(u.index(), v.index());
+                    assert_eq!(
+                        fast_triconnectivity.query_labels(in_graph[u], in_graph[v]),
+                        expected
+                    );
+                    assert_eq!(
+                        fast_triconnectivity.query(u.index(), v.index(), false),
+                        expected
+                    );
+                }
+            }
+        }
+    }
+
     #[cfg(all(test, not(debug_assertions)))]
     #[test]
     fn test_triconnectivity_exhaustive() {
@@ -0,0 +1,140 @@
+use crate::collections::HashSet;
+use petgraph::visit::{EdgeRef, NodeIndexable};
+
+use crate::{UnGraph, block_cut::get_block_cut_tree, spqr_tree::get_spqr_tree};
+
+/// Checks whether a connected graph is series-parallel (has no `K4` minor).
+///
+/// A graph is series-parallel iff no SPQR tree of its blocks contains an R-node,
+/// because every R-node skeleton is triconnected and thus contains a `K4` minor.
+#[allow(dead_code)]
+pub fn is_series_parallel(graph: &UnGraph) -> bool {
+    let bc_tree = get_block_cut_tree(graph);
+
+    bc_tree
+        .blocks
+        .iter()
+        .all(|block| get_spqr_tree(block).is_series_parallel())
+}
+
+/// Returns a proper 3-coloring of a connected series-parallel graph, or `None` if there is an R-node.
+///
+/// Colors are `0`, `1` and `2`, `coloring[v]` is the color of the vertex with internal index `v`.
+///
+/// # Idea:
+/// 1. Using the SPQR trees of blocks, check that the graph is series-parallel.
+/// 2. Series-parallel graphs (and all their subgraphs) always have a vertex with at most 2 neighbors,
+///    so we can remove such vertices one by one.
+/// 3. Color vertices in reversed order of removal: when a vertex is colored, at most 2 of its neighbors
+///    already have a color, so one of 3 colors is free.
+///
+/// Graphs with R-nodes would need an approach based on their embedding, it is not handled here.
+#[allow(dead_code)]
+pub fn three_coloring(graph: &UnGraph) -> Option<Vec<u8>> {
+    if !is_series_parallel(graph) {
+        return None;
+    }
+
+    let n = graph.node_count();
+
+    // parallel edges do not matter for coloring
+    let mut neighbors = vec![HashSet::new(); n];
+    for e in graph.edge_references() {
+        let (u, v) = (e.source().index(), e.target().index());
+        if u != v {
+            neighbors[u].insert(v);
+            neighbors[v].insert(u);
+        }
+    }
+
+    let mut removed = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    let mut stack: Vec<usize> = (0..n).filter(|&u| neighbors[u].len() <= 2).collect();
+
+    while let Some(u) = stack.pop() {
+        if removed[u] {
+            continue;
+        }
+        removed[u] = true;
+        order.push(u);
+
+        let adjacent: Vec<usize> = neighbors[u].drain().collect();
+        for v in adjacent {
+            neighbors[v].remove(&u);
+            if !removed[v] && neighbors[v].len() <= 2 {
+                stack.push(v);
+            }
+        }
+    }
+
+    assert_eq!(
+        order.len(),
+        n,
+        "Series-parallel graph should be 2-degenerate"
+    );
+
+    let mut coloring = vec![u8::MAX; n];
+    for &u in order.iter().rev() {
+        let used: Vec<u8> = graph
+            .neighbors(graph.from_index(u))
+            .map(|v| coloring[v.index()])
+            .collect();
+        coloring[u] = (0..3).find(|c| !used.contains(c)).unwrap();
+    }
+
+    Some(coloring)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::from_str;
+
+    fn is_proper(graph: &UnGraph, coloring: &[u8]) -> bool {
+        coloring.iter().all(|&c| c < 3)
+            && graph
+                .edge_references()
+                .all(|e| coloring[e.source().index()] != coloring[e.target().index()])
+    }
+
+    fn cycle(k: usize) -> UnGraph {
+        let mut input = String::new();
+        for i in 0..k {
+            input.push_str(&format!("{},{}\n", i, (i + 1) % k));
+        }
+        from_str(&input)
+    }
+
+    #[test]
+    fn test_three_coloring_cycles() {
+        for k in 3..10 {
+            let graph = cycle(k);
+            let coloring = three_coloring(&graph).unwrap();
+            assert!(is_proper(&graph, &coloring));
+
+            if k % 2 == 1 {
+                // odd cycles need all 3 colors
+                let used: HashSet<u8> = coloring.iter().copied().collect();
+                assert_eq!(used.len(), 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_three_coloring_series_parallel() {
+        // envelope: cycle with ears, bond between 0 and 2 and a pendant triangle at cut vertex 3
+        let graph =
+            from_str("0,1\n1,2\n2,3\n3,0\n0,4\n3,4\n0,5\n1,5\n1,6\n2,6\n0,2\n0,2\n3,8\n8,9\n9,3\n");
+        assert!(is_series_parallel(&graph));
+        let coloring = three_coloring(&graph).unwrap();
+        assert!(is_proper(&graph, &coloring));
+    }
+
+    #[test]
+    fn test_three_coloring_rigid() {
+        // K4 is the smallest graph which is not series-parallel
+        let graph = from_str("0,1\n0,2\n0,3\n1,2\n1,3\n2,3\n");
+        assert!(!is_series_parallel(&graph));
+        assert!(three_coloring(&graph).is_none());
+    }
+}
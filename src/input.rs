@@ -1,7 +1,8 @@
+use crate::collections::HashMap;
 use crate::{EdgeLabel, UnGraph};
 use embed_doc_image::embed_doc_image;
-use hashbrown::HashMap;
 use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
 use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Cursor};
@@ -18,12 +19,38 @@ use std::io::{BufRead, BufReader, Cursor};
 ///
 /// Your node labels are related to internal node indices: NodeIndex(0) = your smallest label and so on.
 ///
+/// # Optional header
+/// Nodes are inferred from edge endpoints, so isolated vertices would be lost.
+/// To keep them, the first line can be a header in one of the forms:
+/// - `n m` - number of nodes and number of edges (the latter is informational only),
+/// - `# nodes: n`.
+///
+/// Header creates nodes labeled `0, 1, ..., n-1`, edges may still use any other labels.
+///
+/// # Optional edge label
+/// Edge can be written as "u,v,l", where `l` is one of:
+/// - `r` - [`EdgeLabel::Real`],
+/// - `v` - [`EdgeLabel::Virtual`],
+/// - `s` - [`EdgeLabel::Structure`].
+///
+/// Edges without a label are real. This allows reloading structure graphs, e.g. block-cut skeletons.
+///
+/// # Edge order
+/// Edges are added in the order of input lines, so the `i`-th edge line that is not a self-loop
+/// gets `EdgeIndex::new(i)` (self-loops are skipped, see below).
+/// Decompositions keep these indices, e.g. it is also the edge `i` of `TriconnectedComponents::edges`,
+/// which makes their output reproducible with respect to the input.
+///
+/// # Comments
+/// Lines starting with `#` (other than the `# nodes: n` header) are skipped.
+/// For other comment prefixes see [`from_str_with_comments`].
+///
 /// # Warning
 /// <div class="warning">
 ///
 /// - Graph does not have to be connected, but later you will get errors.
 /// - Parser will allow self-loops, but they will be ignored.
-/// - Parallel edges are fully supported.
+/// - Parallel edges are fully supported, except by a few functions listed in [`require_simple`].
 /// - If you want to build your graph manually, remember node labels must be unique.
 ///
 /// </div>
@@ -57,40 +84,283 @@ use std::io::{BufRead, BufReader, Cursor};
 #[embed_doc_image("wheel4", "assets/wheel4.svg")]
 
 pub fn from_str(input: &str) -> UnGraph {
+    from_str_checked(input).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Works like [`from_str`], but returns an error describing the first malformed line instead of panicking.
+///
+/// Use it for input you don't control, e.g. edge lists uploaded by users.
+///
+/// # Code example
+/// ```rust
+/// use spqr_trees::input::{from_str_checked, ParseError};
+///
+/// let graph = from_str_checked("1,2\n2,3\n").unwrap();
+/// assert_eq!(graph.edge_count(), 2);
+///
+/// let err = from_str_checked("1,2\n2,x\n").unwrap_err();
+/// assert_eq!(err, ParseError::NotAnInteger { line: 2, token: "x".to_string() });
+/// ```
+pub fn from_str_checked(input: &str) -> Result<UnGraph, ParseError> {
+    let cursor = Cursor::new(input);
+    let reader = BufReader::new(cursor);
+    parse_graph_from_custom_format(reader, DEFAULT_COMMENT_PREFIXES)
+}
+
+/// Comment prefixes used by [`from_str`] and [`from_file`].
+pub const DEFAULT_COMMENT_PREFIXES: &[&str] = &["#"];
+
+/// Works like [`from_str`], but skips lines starting with any of `comment_prefixes`,
+/// e.g. `&["#", "%", "c"]` for headers of benchmark files.
+///
+/// The `# nodes: n` header is still recognized in the first line, even if `#` is a comment prefix.
+///
+/// # Code example
+/// ```rust
+/// use spqr_trees::input::from_str_with_comments;
+///
+/// let graph = from_str_with_comments("% made by hand\n1,2\nc middle\n2,3\n", &["%", "c"]);
+/// assert_eq!(graph.edge_count(), 2);
+/// ```
+pub fn from_str_with_comments(input: &str, comment_prefixes: &[&str]) -> UnGraph {
     let cursor = Cursor::new(input);
     let reader = BufReader::new(cursor);
-    parse_graph_from_custom_format(reader)
+    parse_graph_from_custom_format(reader, comment_prefixes).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Reads multiple graphs separated by blank lines, each of them is parsed like in [`from_str`].
+///
+/// Labels of each graph are independent, so every graph has its own relation between labels and internal indices.
+///
+/// # Code example
+/// ```rust
+/// use spqr_trees::input::from_str_multi;
+///
+/// let graphs = from_str_multi("1,2\n2,3\n\n5,6\n");
+/// assert_eq!(graphs.len(), 2);
+/// assert_eq!(graphs[1].node_count(), 2);
+/// ```
+pub fn from_str_multi(input: &str) -> Vec<UnGraph> {
+    let mut graphs = Vec::new();
+    let mut block = String::new();
+
+    for line in input.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                graphs.push(from_str(&block));
+                block.clear();
+            }
+        } else {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+
+    graphs
 }
 
 /// This is equivalent to [`from_str`], but takes file path as an input.
 pub fn from_file(path: &str) -> UnGraph {
     let file = File::open(path).expect("File should exist and be readable");
     let reader = BufReader::new(file);
-    parse_graph_from_custom_format(reader)
+    parse_graph_from_custom_format(reader, DEFAULT_COMMENT_PREFIXES)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Reads a graph from a file, choosing the parser by the file extension.
+///
+/// Only our edge list format (see [`from_str`]) is implemented, it is used for any extension
+/// other than the ones listed below.
+///
+/// Extensions of other common formats (`.dimacs`, `.g6`, `.dot`, `.graphml`) are recognized,
+/// but these formats are not supported yet, [`InputError::Unsupported`] is returned for them.
+///
+/// Unlike [`from_file`] it doesn't panic: a file that can't be read gives [`InputError::Io`]
+/// and malformed content gives [`InputError::Parse`].
+pub fn from_file_auto(path: &str) -> Result<UnGraph, InputError> {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+
+    match extension {
+        "dimacs" | "g6" | "dot" | "graphml" => Err(InputError::Unsupported(extension.to_string())),
+        _ => {
+            let content = std::fs::read_to_string(path)
+                .map_err(|err| InputError::Io(format!("{}: {}", path, err)))?;
+            from_str_checked(&content).map_err(InputError::Parse)
+        }
+    }
+}
+
+/// Reason why the input couldn't be parsed, see [`from_str_checked`].
+///
+/// Lines are numbered from 1 and count every line of the input, including empty lines and comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Some field of an edge is empty, e.g. `1,` or `1,2,`.
+    EmptyField { line: usize },
+    /// A vertex label or a number in the header is not a non-negative integer.
+    NotAnInteger { line: usize, token: String },
+    /// An edge has a wrong number of fields, expected `u,v` or `u,v,l`.
+    WrongArity { line: usize, fields: usize },
+    /// An edge label is not one of `r`, `v`, `s`.
+    UnknownLabel { line: usize, label: String },
+    /// The first line is neither an edge nor a header `n m` or `# nodes: n`.
+    WrongHeader { line: usize },
+}
+
+impl ParseError {
+    /// Returns the number of the offending line.
+    pub fn line(&self) -> usize {
+        match self {
+            ParseError::EmptyField { line }
+            | ParseError::NotAnInteger { line, .. }
+            | ParseError::WrongArity { line, .. }
+            | ParseError::UnknownLabel { line, .. }
+            | ParseError::WrongHeader { line } => *line,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Wrong format in line {}: ", self.line())?;
+        match self {
+            ParseError::EmptyField { .. } => write!(f, "empty field"),
+            ParseError::NotAnInteger { token, .. } => {
+                write!(f, "'{}' should be a non-negative number", token)
+            }
+            ParseError::WrongArity { fields, .. } => write!(
+                f,
+                "expected 'u,v' or 'u,v,l' for an edge, got {} fields",
+                fields
+            ),
+            ParseError::UnknownLabel { label, .. } => write!(
+                f,
+                "edge label should be one of 'r', 'v', 's', got '{}'",
+                label
+            ),
+            ParseError::WrongHeader { .. } => {
+                write!(f, "expected 'n m' or '# nodes: n' header")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Reason why a graph can't be read or passed to an algorithm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    /// There are at least two edges between vertices with these labels.
+    ParallelEdges(u32, u32),
+    /// There is an edge from the vertex with this label to itself.
+    SelfLoop(u32),
+    /// The file has an extension of a format we can't read yet, see [`from_file_auto`].
+    Unsupported(String),
+    /// The file can't be read, with the path and the reason.
+    Io(String),
+    /// The file is not in our edge list format.
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::ParallelEdges(u, v) => write!(
+                f,
+                "Graph must be simple, but there are parallel edges between {} and {}",
+                u, v
+            ),
+            InputError::SelfLoop(v) => {
+                write!(f, "Graph must be simple, but there is a self-loop at {}", v)
+            }
+            InputError::Unsupported(extension) => {
+                write!(f, "Format '.{}' is not supported yet", extension)
+            }
+            InputError::Io(reason) => write!(f, "Can't read file {}", reason),
+            InputError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Checks that the graph has no parallel edges and no self-loops.
+///
+/// Reported vertices are labels, the first offending edge (by edge index) is reported.
+///
+/// # Multigraphs
+/// Parallel edges are fully supported by block-cut trees, triconnected components and SPQR trees
+/// (they become bonds), by the planarity test and by functions built on top of them.
+///
+/// Functions which need a simple graph call this check first and panic with the error, these are:
+/// - [`triangulate`](crate::drawing_blocks::triangulate::triangulate),
+/// - [`embed_with_rotation`](crate::types::embed_with_rotation), as rotations can't tell parallel edges apart.
+///
+/// If you have a multigraph, see [`simplify`](crate::types::simplify).
+pub fn require_simple(graph: &UnGraph) -> Result<(), InputError> {
+    let mut seen = BTreeSet::new();
+    for e in graph.edge_references() {
+        let (a, b) = (graph[e.source()], graph[e.target()]);
+        if a == b {
+            return Err(InputError::SelfLoop(a));
+        }
+        if !seen.insert((a.min(b), a.max(b))) {
+            return Err(InputError::ParallelEdges(a.min(b), a.max(b)));
+        }
+    }
+    Ok(())
 }
 
-fn parse_graph_from_custom_format<R: BufRead>(reader: R) -> UnGraph {
+fn parse_graph_from_custom_format<R: BufRead>(
+    reader: R,
+    comment_prefixes: &[&str],
+) -> Result<UnGraph, ParseError> {
     let mut edges = Vec::new();
     // we pay additional O(log n), but we are gaining relation between node labels and internal ids
     let mut node_ids = BTreeSet::<u32>::new();
     let mut ids_to_internal = HashMap::<u32, NodeIndex>::new();
+    let mut first_line = true;
 
-    for line in reader.lines() {
+    for (i, line) in reader.lines().enumerate() {
+        let line_no = i + 1;
         let line = line.expect("Line should be readable");
         let line = line.trim();
         if line.is_empty() {
             continue;
         }
+        let nodes_header = first_line
+            && line
+                .strip_prefix('#')
+                .is_some_and(|rest| rest.trim().starts_with("nodes:"));
+        if !nodes_header && comment_prefixes.iter().any(|p| line.starts_with(p)) {
+            continue;
+        }
+        if first_line {
+            first_line = false;
+            if let Some(n) = parse_header(line, line_no)? {
+                node_ids.extend(0..n);
+                continue;
+            }
+        }
         let pair: Vec<_> = line.split(',').collect();
-        if pair.len() != 2 {
-            panic!("Wrong format, expected 'u,v' for an edge");
+        if pair.len() != 2 && pair.len() != 3 {
+            return Err(ParseError::WrongArity {
+                line: line_no,
+                fields: pair.len(),
+            });
         }
-        let u: u32 = pair[0]
-            .parse()
-            .expect("Node index should be a non-negative number");
-        let v: u32 = pair[1]
-            .parse()
-            .expect("Node index should be a non-negative number");
+        if pair.iter().any(|field| field.trim().is_empty()) {
+            return Err(ParseError::EmptyField { line: line_no });
+        }
+        let u = parse_number(pair[0], line_no)?;
+        let v = parse_number(pair[1], line_no)?;
+        let label = match pair.get(2) {
+            Some(l) => parse_edge_label(l, line_no)?,
+            None => EdgeLabel::Real,
+        };
 
         if u == v {
             continue;
@@ -99,7 +369,7 @@ fn parse_graph_from_custom_format<R: BufRead>(reader: R) -> UnGraph {
         node_ids.insert(u);
         node_ids.insert(v);
 
-        edges.push((u, v));
+        edges.push((u, v, label));
     }
 
     let mut graph = UnGraph::new_undirected();
@@ -111,17 +381,182 @@ fn parse_graph_from_custom_format<R: BufRead>(reader: R) -> UnGraph {
 
     graph.extend_with_edges(
         edges
-            .iter()
-            .map(|&(u, v)| (ids_to_internal[&u], ids_to_internal[&v], EdgeLabel::Real)),
+            .into_iter()
+            .map(|(u, v, label)| (ids_to_internal[&u], ids_to_internal[&v], label)),
     );
 
-    graph
+    Ok(graph)
+}
+
+fn parse_number(token: &str, line: usize) -> Result<u32, ParseError> {
+    token.parse().map_err(|_| ParseError::NotAnInteger {
+        line,
+        token: token.to_string(),
+    })
+}
+
+fn parse_edge_label(label: &str, line: usize) -> Result<EdgeLabel, ParseError> {
+    match label.trim() {
+        "r" => Ok(EdgeLabel::Real),
+        "v" => Ok(EdgeLabel::Virtual),
+        "s" => Ok(EdgeLabel::Structure),
+        _ => Err(ParseError::UnknownLabel {
+            line,
+            label: label.to_string(),
+        }),
+    }
+}
+
+/// Returns the number of nodes if the line is a header, `None` if it is an edge.
+fn parse_header(line: &str, line_no: usize) -> Result<Option<u32>, ParseError> {
+    if line.contains(',') {
+        return Ok(None);
+    }
+
+    let n = if let Some(rest) = line.strip_prefix('#') {
+        rest.trim()
+            .strip_prefix("nodes:")
+            .ok_or(ParseError::WrongHeader { line: line_no })?
+            .trim()
+    } else {
+        let header: Vec<_> = line.split_whitespace().collect();
+        if header.len() != 2 {
+            return Err(ParseError::WrongHeader { line: line_no });
+        }
+        parse_number(header[1], line_no)?;
+        header[0]
+    };
+
+    parse_number(n, line_no).map(Some)
 }
 
 #[cfg(test)]
 mod input_tests {
     use super::*;
 
+    #[test]
+    fn test_from_file_auto() {
+        // names are unique per process, so parallel runs don't overwrite each other's files
+        let dir = std::env::temp_dir();
+        let name = format!("spqr_trees_from_file_auto_{}", std::process::id());
+        let edge_list = dir.join(format!("{}.in", name));
+        let no_extension = dir.join(name);
+        std::fs::write(&edge_list, "1,2\n2,3\n3,1\n").unwrap();
+        std::fs::write(&no_extension, "1,2\n2,3\n").unwrap();
+
+        let graph = from_file_auto(edge_list.to_str().unwrap()).unwrap();
+        assert_eq!((graph.node_count(), graph.edge_count()), (3, 3));
+        let graph = from_file_auto(no_extension.to_str().unwrap()).unwrap();
+        assert_eq!((graph.node_count(), graph.edge_count()), (3, 2));
+
+        std::fs::remove_file(edge_list).unwrap();
+        std::fs::remove_file(no_extension).unwrap();
+    }
+
+    #[test]
+    fn test_from_file_auto_unsupported() {
+        let err = from_file_auto("graph.g6").unwrap_err();
+        assert_eq!(err, InputError::Unsupported("g6".to_string()));
+        assert_eq!(err.to_string(), "Format '.g6' is not supported yet");
+    }
+
+    #[test]
+    fn test_self_loop_lines_get_no_edge_index() {
+        use petgraph::graph::EdgeIndex;
+
+        // the self-loop in the second line is skipped, so the third line is edge 1
+        let graph = from_str("1,2\n3,3\n2,3\n");
+        assert_eq!(graph.edge_count(), 2);
+        let (s, t) = graph.edge_endpoints(EdgeIndex::new(1)).unwrap();
+        assert_eq!((graph[s], graph[t]), (2, 3));
+    }
+
+    #[test]
+    fn test_from_file_auto_errors() {
+        let dir = std::env::temp_dir();
+        let missing = dir.join(format!("spqr_trees_missing_{}.in", std::process::id()));
+        assert!(matches!(
+            from_file_auto(missing.to_str().unwrap()),
+            Err(InputError::Io(_))
+        ));
+
+        let malformed = dir.join(format!("spqr_trees_malformed_{}.in", std::process::id()));
+        std::fs::write(&malformed, "1,2\n2,x\n").unwrap();
+        assert_eq!(
+            from_file_auto(malformed.to_str().unwrap()).unwrap_err(),
+            InputError::Parse(ParseError::NotAnInteger {
+                line: 2,
+                token: "x".to_string()
+            })
+        );
+        std::fs::remove_file(malformed).unwrap();
+    }
+
+    #[test]
+    fn test_from_str_checked_errors() {
+        let cases = [
+            ("1,2\n2,3,\n", ParseError::EmptyField { line: 2 }),
+            ("1,2\n\n# comment\n,3\n", ParseError::EmptyField { line: 4 }),
+            (
+                "1,2\n2,a\n",
+                ParseError::NotAnInteger {
+                    line: 2,
+                    token: "a".to_string(),
+                },
+            ),
+            (
+                "-1,2\n",
+                ParseError::NotAnInteger {
+                    line: 1,
+                    token: "-1".to_string(),
+                },
+            ),
+            (
+                "1,2\n2,3,4,5\n",
+                ParseError::WrongArity { line: 2, fields: 4 },
+            ),
+            ("1,2\n3\n", ParseError::WrongArity { line: 2, fields: 1 }),
+            (
+                "1,2,x\n",
+                ParseError::UnknownLabel {
+                    line: 1,
+                    label: "x".to_string(),
+                },
+            ),
+            ("1 2 3\n1,2\n", ParseError::WrongHeader { line: 1 }),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(from_str_checked(input).unwrap_err(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_from_str_checked_valid() {
+        let input = "# nodes: 4\n1,2\n2,3,v\n3,3\n";
+        let graph = from_str_checked(input).unwrap();
+        assert_eq!((graph.node_count(), graph.edge_count()), (4, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong format in line 2: 'a' should be a non-negative number")]
+    fn test_from_str_panics_with_line() {
+        from_str("1,2\na,b\n");
+    }
+
+    #[test]
+    fn test_require_simple() {
+        assert_eq!(require_simple(&from_str("1,2\n2,3\n3,1\n")), Ok(()));
+        assert_eq!(
+            require_simple(&from_str("1,2\n2,3\n3,2\n")),
+            Err(InputError::ParallelEdges(2, 3))
+        );
+
+        let mut graph = from_str("1,2\n");
+        graph.add_edge(0.into(), 0.into(), EdgeLabel::Real);
+        assert_eq!(require_simple(&graph), Err(InputError::SelfLoop(1)));
+    }
+
     #[test]
     fn test_from_str_empty() {
         let input = "";
@@ -150,4 +585,78 @@ mod input_tests {
         assert!(graph.contains_edge(0.into(), 1.into()));
         assert!(graph.contains_edge(1.into(), 2.into()));
     }
+
+    #[test]
+    fn test_from_str_with_header() {
+        let input = "5 2\n0,1\n1,2";
+        let graph = from_str(input);
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 2);
+        // isolated vertices are kept
+        assert_eq!(graph[NodeIndex::new(3)], 3);
+        assert_eq!(graph[NodeIndex::new(4)], 4);
+        assert_eq!(graph.neighbors(NodeIndex::new(3)).count(), 0);
+        assert_eq!(graph.neighbors(NodeIndex::new(4)).count(), 0);
+    }
+
+    #[test]
+    fn test_from_str_with_comment_header() {
+        let input = "# nodes: 4\n0,1\n1,2";
+        let graph = from_str(input);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph[NodeIndex::new(3)], 3);
+    }
+
+    #[test]
+    fn test_from_str_with_comments() {
+        let input = "# generated graph\n0,1\n# middle comment\n1,2\n  # indented\n2,0\n";
+        let graph = from_str(input);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+
+        // comments before the header don't hide it
+        let graph = from_str("# comment\n5 1\n0,1\n");
+        assert_eq!(graph.node_count(), 5);
+
+        let input = "c dimacs-like\n% matrix market\n0,1\nc\n1,2\n";
+        let graph = from_str_with_comments(input, &["c", "%"]);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn test_from_str_with_edge_labels() {
+        let input = "0,1,s\n1,2,r\n2,0,v\n0,2";
+        let graph = from_str(input);
+        assert_eq!(graph.edge_count(), 4);
+        let labels: Vec<_> = graph.edge_weights().cloned().collect();
+        assert_eq!(
+            labels,
+            vec![
+                EdgeLabel::Structure,
+                EdgeLabel::Real,
+                EdgeLabel::Virtual,
+                EdgeLabel::Real
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_str_multi() {
+        let input = "\n3,7\n7,9\n\n\n0,1\n1,2\n2,0\n   \n";
+        let graphs = from_str_multi(input);
+        assert_eq!(graphs.len(), 2);
+
+        assert_eq!(graphs[0].node_count(), 3);
+        assert_eq!(graphs[0].edge_count(), 2);
+        assert_eq!(graphs[0][NodeIndex::new(0)], 3);
+        assert_eq!(graphs[0][NodeIndex::new(2)], 9);
+
+        assert_eq!(graphs[1].node_count(), 3);
+        assert_eq!(graphs[1].edge_count(), 3);
+        assert_eq!(graphs[1][NodeIndex::new(0)], 0);
+
+        assert!(from_str_multi("").is_empty());
+    }
 }
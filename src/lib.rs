@@ -30,11 +30,12 @@
 //!
 //! For examples of usage, see `examples`, `src/example_usages` and `tests`.
 pub mod block_cut;
+pub(crate) mod collections;
 pub mod input;
 pub mod output;
 pub mod spqr_blocks;
 pub mod spqr_tree;
-pub(crate) mod testing;
+pub mod testing;
 pub mod triconnected;
 pub mod triconnected_blocks;
 
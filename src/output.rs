@@ -1,45 +1,396 @@
 use crate::UnGraph;
+use crate::triconnected_blocks::{
+    graph_internal::{EdgeType, GraphInternal},
+    palm_dfs::run_palm_forest,
+};
+use crate::types::sorted_edges;
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 
 /// Returns a graph in DOT format.
 ///
 /// It shows your nodes labels, not petgraph's internal indices.
+/// Nodes are identified by their ranks in the order of labels (ties broken by internal indices),
+/// so nodes with equal labels stay distinct.
 ///
 /// It adds colors also.
 ///
 /// Real edges and structure edges are solid and virtual edges are dashed.
 ///
+/// Output is canonical: node and edge statements are sorted by ranks,
+/// so graphs with the same labeled nodes and edges give the same output regardless of the order
+/// in which nodes and edges were added, as long as labels are distinct.
+///
 /// Intended to be used with `neato`.
 pub fn draw_graph(graph: &UnGraph) -> String {
     let mut output = String::from("graph {\n");
     output.push_str("  mode=sgd;\n");
     output.push_str("  maxiter=1000;\n");
     output.push_str("  node [shape=circle, style=filled, fillcolor=lightblue];\n");
-    
+
     // Add vertices
-    for node_idx in graph.node_indices() {
-        let label = graph.node_weight(node_idx).unwrap();
+    let (nodes, rank) = sorted_nodes(graph);
+    for (i, v) in nodes.into_iter().enumerate() {
+        output.push_str(&format!("  {} [label=\"{}\"];\n", i, graph[v]));
+    }
+
+    // Add edges
+    let mut edges = Vec::with_capacity(graph.edge_count());
+    for edge in graph.edge_references() {
+        let (a, b) = (rank[edge.source().index()], rank[edge.target().index()]);
+        edges.push((a.min(b), a.max(b), edge_style(*edge.weight())));
+    }
+    edges.sort();
+    for (a, b, style) in edges {
+        output.push_str(&format!("  {} -- {} [style={}];\n", a, b, style));
+    }
+    output.push_str("}\n");
+    output
+}
+
+/// Returns a graph in DOT format as a directed graph, each edge is oriented by `direction`.
+///
+/// `direction(e)` returns `(source, target)` of edge `e`, these must be the endpoints of `e`.
+/// It allows drawing e.g. a DFS orientation of an undirected graph.
+///
+/// Besides orientation, output looks like the one of [`draw_graph`].
+///
+/// Intended to be used with `dot`.
+pub fn draw_digraph(
+    graph: &UnGraph,
+    direction: impl Fn(EdgeIndex) -> (NodeIndex, NodeIndex),
+) -> String {
+    render_digraph(
+        graph,
+        direction,
+        |_| String::new(),
+        |e| format!("style={}", edge_style(graph[e])),
+    )
+}
+
+/// Shared emitter of directed DOT drawings, e.g. [`draw_digraph`], [`draw_palm_forest`] and [`crate::block_cut::draw_bc_tree_dfs`].
+///
+/// `node_attributes(v)` are appended after the label of `v` (empty for none),
+/// `edge_attributes(e)` are all attributes of `e`, e.g. `style=dashed`.
+/// Nodes are identified and statements are sorted as in [`draw_graph`].
+pub(crate) fn render_digraph(
+    graph: &UnGraph,
+    direction: impl Fn(EdgeIndex) -> (NodeIndex, NodeIndex),
+    node_attributes: impl Fn(NodeIndex) -> String,
+    edge_attributes: impl Fn(EdgeIndex) -> String,
+) -> String {
+    let mut output = String::from("digraph {\n");
+    output.push_str("  node [shape=circle, style=filled, fillcolor=lightblue];\n");
+
+    // Add vertices
+    let (nodes, rank) = sorted_nodes(graph);
+    for (i, v) in nodes.into_iter().enumerate() {
+        let attributes = node_attributes(v);
+        let separator = if attributes.is_empty() { "" } else { ", " };
         output.push_str(&format!(
-            "  {} [label=\"{}\"];\n",
-            node_idx.index(),
-            label
+            "  {} [label=\"{}\"{}{}];\n",
+            i, graph[v], separator, attributes
         ));
     }
-    
+
     // Add edges
+    let mut edges = Vec::with_capacity(graph.edge_count());
+    for edge in graph.edge_references() {
+        let (s, t) = direction(edge.id());
+        assert!(
+            (s, t) == (edge.source(), edge.target()) || (t, s) == (edge.source(), edge.target()),
+            "Direction should return endpoints of the edge"
+        );
+        edges.push((rank[s.index()], rank[t.index()], edge_attributes(edge.id())));
+    }
+    edges.sort();
+    for (a, b, attributes) in edges {
+        output.push_str(&format!("  {} -> {} [{}];\n", a, b, attributes));
+    }
+    output.push_str("}\n");
+    output
+}
+
+/// Nodes sorted by labels, nodes with equal labels by internal indices,
+/// and the rank of every node in this order, by internal index.
+fn sorted_nodes(graph: &UnGraph) -> (Vec<NodeIndex>, Vec<usize>) {
+    let mut nodes: Vec<NodeIndex> = graph.node_indices().collect();
+    nodes.sort_by_key(|&v| (graph[v], v));
+    let mut rank = vec![0; graph.node_count()];
+    for (i, v) in nodes.iter().enumerate() {
+        rank[v.index()] = i;
+    }
+    (nodes, rank)
+}
+
+/// Style of an edge with `label`, shared by DOT and TikZ drawings, which use the same style names.
+fn edge_style(label: crate::EdgeLabel) -> &'static str {
+    if label == crate::EdgeLabel::Virtual {
+        "dashed"
+    } else {
+        "solid"
+    }
+}
+
+/// Returns the palm tree (DFS tree used by the triconnected components algorithm) as a plain-text table.
+///
+/// DFS starts at the node with internal index 0. There is one row per node, sorted by rank (preorder number),
+/// with columns: node label, rank, `low1`, `low2`, parent label (`-` for a root) and subtree size.
+/// `low1` and `low2` are ranks, see `triconnected_blocks::palm_dfs` for their meaning.
+///
+/// Disconnected graphs give a palm forest, each component has its own root.
+pub fn palm_tree_table(graph: &UnGraph) -> String {
+    let mut palm = GraphInternal::from_petgraph(graph);
+    run_palm_forest(&mut palm);
+
+    let mut order: Vec<usize> = (0..palm.n).collect();
+    order.sort_by_key(|&u| palm.num[u]);
+
+    let mut output = format!(
+        "{:>6} {:>6} {:>6} {:>6} {:>6} {:>6}\n",
+        "node", "rank", "low1", "low2", "parent", "sub"
+    );
+    let label = |v: usize| graph[NodeIndex::new(v)];
+    for u in order {
+        let parent = palm.par[u].map_or("-".to_string(), |p| label(p).to_string());
+        output.push_str(&format!(
+            "{:>6} {:>6} {:>6} {:>6} {:>6} {:>6}\n",
+            label(u),
+            palm.num[u],
+            palm.low1[u],
+            palm.low2[u],
+            parent,
+            palm.sub[u]
+        ));
+    }
+    output
+}
+
+/// Returns the palm forest of the graph in DOT format.
+///
+/// Tree edges are solid and point down, back edges are dashed and point up (towards the ancestor).
+/// Roots (one per connected component) are green, other nodes show their labels as in [`draw_graph`].
+///
+/// Intended to be used with `dot`.
+pub fn draw_palm_forest(graph: &UnGraph) -> String {
+    let mut palm = GraphInternal::from_petgraph(graph);
+    let roots = run_palm_forest(&mut palm);
+
+    // edges of `palm` are edges of `graph` in the same order, oriented by the DFS
+    render_digraph(
+        graph,
+        |e| {
+            let (s, t) = palm.edges[e.index()];
+            (NodeIndex::new(s), NodeIndex::new(t))
+        },
+        |v| {
+            if roots.contains(&v.index()) {
+                "fillcolor=green".to_string()
+            } else {
+                String::new()
+            }
+        },
+        |e| {
+            if palm.edge_type[e.index()] == Some(EdgeType::Tree) {
+                "style=solid".to_string()
+            } else {
+                "style=dashed".to_string()
+            }
+        },
+    )
+}
+
+/// Returns a `tikzpicture` drawing the graph with vertex `v` placed at `pos[v]`.
+///
+/// `pos` is indexed by internal indices, vertices are named `v<index>` and show their labels.
+///
+/// Real edges and structure edges are solid and virtual edges are dashed, as in [`draw_graph`].
+pub fn to_tikz(graph: &UnGraph, pos: &[(f64, f64)]) -> String {
+    assert_eq!(pos.len(), graph.node_count());
+
+    let mut output = String::from("\\begin{tikzpicture}\n");
+    for v in graph.node_indices() {
+        let (x, y) = pos[v.index()];
+        output.push_str(&format!(
+            "  \\node[circle, draw, fill=blue!20] (v{}) at ({:.3}, {:.3}) {{{}}};\n",
+            v.index(),
+            x,
+            y,
+            graph[v]
+        ));
+    }
     for edge in graph.edge_references() {
-        let (a, b) = (edge.source().index(), edge.target().index());
-        let style = if *edge.weight() == crate::EdgeLabel::Virtual {
-            "dashed"
-        } else {
-            "solid"
+        output.push_str(&format!(
+            "  \\draw[{}] (v{}) -- (v{});\n",
+            edge_style(*edge.weight()),
+            edge.source().index(),
+            edge.target().index()
+        ));
+    }
+    output.push_str("\\end{tikzpicture}\n");
+    output
+}
+
+/// Returns the graph in the edge list format read by [`crate::input::from_str`].
+///
+/// One line `u,v` per edge with `u <= v` being node labels, lines are sorted.
+/// Virtual and structure edges get a `,v` or `,s` suffix, so labels of edges survive a round trip.
+///
+/// Isolated vertices are not written.
+pub fn to_edge_list(graph: &UnGraph) -> String {
+    let mut output = String::new();
+    for (a, b, label) in sorted_edges(graph) {
+        let suffix = match label {
+            crate::EdgeLabel::Real => "",
+            crate::EdgeLabel::Virtual => ",v",
+            crate::EdgeLabel::Structure => ",s",
         };
+        output.push_str(&format!("{},{}{}\n", a, b, suffix));
+    }
+    output
+}
+
+/// Returns an SVG drawing of the graph with vertices placed on a circle in increasing order of labels.
+///
+/// Real edges and structure edges are solid and virtual edges are dashed, as in [`draw_graph`].
+/// Hovering over a vertex shows its label and degree.
+pub fn to_svg(graph: &UnGraph) -> String {
+    let mut order: Vec<NodeIndex> = graph.node_indices().collect();
+    order.sort_by_key(|&v| graph[v]);
+    let mut position = vec![0; graph.node_count()];
+    for (i, v) in order.iter().enumerate() {
+        position[v.index()] = i;
+    }
+
+    let labels: Vec<String> = order.iter().map(|&v| graph[v].to_string()).collect();
+    let edges: Vec<(usize, usize, bool)> = graph
+        .edge_references()
+        .map(|e| {
+            (
+                position[e.source().index()],
+                position[e.target().index()],
+                *e.weight() == crate::EdgeLabel::Virtual,
+            )
+        })
+        .collect();
+    circular_svg(&labels, &edges)
+}
+
+/// Draws vertices with `labels` on a circle, `edges` are `(u, v, dashed)`.
+fn circular_svg(labels: &[String], edges: &[(usize, usize, bool)]) -> String {
+    let size = 400.0;
+    let radius = if labels.len() > 1 { 160.0 } else { 0.0 };
+    let pos = |i: usize| {
+        let angle = 2.0 * std::f64::consts::PI * i as f64 / labels.len() as f64;
+        (
+            size / 2.0 + radius * angle.cos(),
+            size / 2.0 + radius * angle.sin(),
+        )
+    };
+
+    let mut degree = vec![0; labels.len()];
+    let mut output = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\">\n",
+        size, size
+    );
+    for &(u, v, dashed) in edges {
+        degree[u] += 1;
+        degree[v] += 1;
+        let ((x1, y1), (x2, y2)) = (pos(u), pos(v));
         output.push_str(&format!(
-            "  {} -- {} [style={}];\n",
-            a, b, style
+            "  <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\"{} />\n",
+            x1,
+            y1,
+            x2,
+            y2,
+            if dashed {
+                " stroke-dasharray=\"6,4\""
+            } else {
+                ""
+            }
         ));
     }
-    output.push_str("}\n");
+    for (i, label) in labels.iter().enumerate() {
+        let (x, y) = pos(i);
+        output.push_str(&format!(
+            "  <g><title>{} (degree {})</title><circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"14\" fill=\"lightblue\" stroke=\"black\" />\
+             <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"central\" font-size=\"11\">{}</text></g>\n",
+            label, degree[i], x, y, x, y, label
+        ));
+    }
+    output.push_str("</svg>\n");
+    output
+}
+
+/// Returns a self-contained HTML page showing the graph, its block-cut tree
+/// and SPQR trees of its blocks as inline SVGs (see [`to_svg`]).
+///
+/// In the block-cut tree blocks are named `B<i>` and cut vertices show their labels.
+/// Components of SPQR trees are named by their type and index, e.g. `S0`.
+/// Bridges have trivial decompositions, so there are no SPQR trees for them.
+pub fn to_html_report(graph: &UnGraph) -> String {
+    let bct = crate::block_cut::get_block_cut_tree(graph);
+
+    let mut output = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    output.push_str("<title>Decomposition report</title>\n");
+    output.push_str("<style>svg { width: 400px; border: 1px solid #ccc; }</style>\n");
+    output.push_str("</head>\n<body>\n");
+
+    output.push_str(&format!(
+        "<h1>Graph</h1>\n<p>{} vertices, {} edges</p>\n",
+        graph.node_count(),
+        graph.edge_count()
+    ));
+    output.push_str(&to_svg(graph));
+
+    output.push_str(&format!(
+        "<h1>Block-cut tree</h1>\n<p>{} blocks, {} cut vertices</p>\n",
+        bct.block_count, bct.cut_count
+    ));
+    let labels: Vec<String> = bct
+        .graph
+        .node_indices()
+        .map(|v| {
+            if v.index() < bct.block_count {
+                format!("B{}", v.index())
+            } else {
+                bct.graph[v].to_string()
+            }
+        })
+        .collect();
+    let edges: Vec<(usize, usize, bool)> = bct
+        .graph
+        .edge_references()
+        .map(|e| (e.source().index(), e.target().index(), false))
+        .collect();
+    output.push_str(&circular_svg(&labels, &edges));
+
+    for (b, block) in bct.blocks.iter().enumerate() {
+        if block.edge_count() == 0 || bct.is_bridge_block(b) {
+            continue;
+        }
+        let spqr_tree = crate::spqr_tree::get_spqr_tree(block);
+        output.push_str(&format!(
+            "<h1>SPQR tree of B{}</h1>\n<p>{} components</p>\n",
+            b,
+            spqr_tree.adj.len()
+        ));
+        let labels: Vec<String> = spqr_tree
+            .blocks
+            .comp
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{}{}", c.comp_type, i))
+            .collect();
+        let edges: Vec<(usize, usize, bool)> = (0..spqr_tree.adj.len())
+            .flat_map(|u| spqr_tree.adj[u].iter().map(move |&v| (u, v)))
+            .filter(|&(u, v)| u < v)
+            .map(|(u, v)| (u, v, false))
+            .collect();
+        output.push_str(&circular_svg(&labels, &edges));
+    }
+
+    output.push_str("</body>\n</html>\n");
     output
 }
 
@@ -53,3 +404,216 @@ pub fn to_dot_file(graph: &UnGraph, path: &str) {
 pub fn to_file(content: &str, path: &str) {
     std::fs::write(path, content).expect("Rust should write to file");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EdgeLabel;
+
+    #[test]
+    fn test_draw_graph_canonical() {
+        let mut graph = UnGraph::new_undirected();
+        let a = graph.add_node(1);
+        let b = graph.add_node(2);
+        let c = graph.add_node(3);
+        graph.add_edge(a, b, EdgeLabel::Real);
+        graph.add_edge(b, c, EdgeLabel::Virtual);
+        graph.add_edge(c, a, EdgeLabel::Real);
+
+        // same labeled graph, but edges are added in a different order and direction
+        let mut reordered = UnGraph::new_undirected();
+        let a = reordered.add_node(1);
+        let b = reordered.add_node(2);
+        let c = reordered.add_node(3);
+        reordered.add_edge(a, c, EdgeLabel::Real);
+        reordered.add_edge(c, b, EdgeLabel::Virtual);
+        reordered.add_edge(b, a, EdgeLabel::Real);
+
+        assert_eq!(draw_graph(&graph), draw_graph(&reordered));
+        assert!(draw_graph(&graph).contains("  1 [label=\"2\"];\n"));
+        assert!(draw_graph(&graph).contains("  1 -- 2 [style=dashed];\n"));
+    }
+
+    #[test]
+    fn test_draw_graph_canonical_node_order() {
+        let mut graph = UnGraph::new_undirected();
+        let a = graph.add_node(10);
+        let b = graph.add_node(20);
+        let c = graph.add_node(30);
+        let d = graph.add_node(40);
+        graph.add_edge(a, b, EdgeLabel::Real);
+        graph.add_edge(b, c, EdgeLabel::Virtual);
+        graph.add_edge(c, d, EdgeLabel::Real);
+        graph.add_edge(d, a, EdgeLabel::Real);
+
+        // same labeled graph, but nodes are added in a permuted order
+        let mut permuted = UnGraph::new_undirected();
+        let c = permuted.add_node(30);
+        let a = permuted.add_node(10);
+        let d = permuted.add_node(40);
+        let b = permuted.add_node(20);
+        permuted.add_edge(d, a, EdgeLabel::Real);
+        permuted.add_edge(c, d, EdgeLabel::Real);
+        permuted.add_edge(b, c, EdgeLabel::Virtual);
+        permuted.add_edge(a, b, EdgeLabel::Real);
+
+        assert_eq!(draw_graph(&graph), draw_graph(&permuted));
+        assert!(draw_graph(&permuted).contains("  0 [label=\"10\"];\n"));
+        assert!(draw_graph(&permuted).contains("  1 -- 2 [style=dashed];\n"));
+    }
+
+    #[test]
+    fn test_draw_graph_duplicate_labels() {
+        // a path 5 - 5 - 5, nodes with equal labels must not be merged
+        let mut graph = UnGraph::new_undirected();
+        let a = graph.add_node(5);
+        let b = graph.add_node(5);
+        let c = graph.add_node(5);
+        graph.add_edge(a, b, EdgeLabel::Real);
+        graph.add_edge(b, c, EdgeLabel::Real);
+
+        let dot = draw_graph(&graph);
+        assert_eq!(dot.matches("[label=\"5\"]").count(), 3);
+        assert!(dot.contains("  0 -- 1 [style=solid];\n"));
+        assert!(dot.contains("  1 -- 2 [style=solid];\n"));
+
+        let dot = draw_digraph(&graph, |e| graph.edge_endpoints(e).unwrap());
+        assert_eq!(dot.matches("[label=\"5\"]").count(), 3);
+        assert!(dot.contains("  0 -> 1 [style=solid];\n"));
+        assert!(dot.contains("  1 -> 2 [style=solid];\n"));
+    }
+
+    #[test]
+    fn test_palm_tree_table() {
+        // triangle with a pendant path: 10 - 20 - 30 - 10, 30 - 40
+        let graph = crate::input::from_str("10,20\n20,30\n30,10\n30,40\n");
+        let table = palm_tree_table(&graph);
+        let rows: Vec<Vec<&str>> = table
+            .lines()
+            .skip(1)
+            .map(|l| l.split_whitespace().collect())
+            .collect();
+
+        assert_eq!(rows.len(), graph.node_count());
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), 6);
+            assert_eq!(row[1], i.to_string());
+        }
+
+        // root is the node with internal index 0, i.e. the smallest label
+        assert_eq!(rows[0][0], "10");
+        assert_eq!(rows[0][4], "-");
+        assert_eq!(rows[0][5], "4");
+    }
+
+    #[test]
+    fn test_palm_forest_two_components() {
+        // triangle 0 - 1 - 2 and a separate edge 3 - 4
+        let graph = crate::input::from_str("0,1\n1,2\n2,0\n3,4\n");
+
+        let table = palm_tree_table(&graph);
+        let roots: Vec<&str> = table
+            .lines()
+            .skip(1)
+            .filter(|l| l.split_whitespace().nth(4) == Some("-"))
+            .map(|l| l.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(roots, vec!["0", "3"]);
+
+        let dot = draw_palm_forest(&graph);
+        assert_eq!(dot.matches("fillcolor=green").count(), 2);
+        // labels 0, ..., 4 are also internal indices
+        assert!(dot.contains("  3 [label=\"3\", fillcolor=green];\n"));
+        assert!(dot.contains("  3 -> 4 [style=solid];\n"));
+        assert_eq!(dot.matches("style=dashed").count(), 1);
+    }
+
+    #[test]
+    fn test_draw_digraph_path() {
+        let graph = crate::input::from_str("3,2\n1,2\n3,4\n");
+
+        // orient the path from the smaller label to the larger one
+        let dot = draw_digraph(&graph, |e| {
+            let (s, t) = graph.edge_endpoints(e).unwrap();
+            if graph[s] < graph[t] { (s, t) } else { (t, s) }
+        });
+
+        // nodes are numbered by ranks of their labels, labels 1, 2, 3, 4 get numbers 0, 1, 2, 3
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.contains("  0 [label=\"1\"];\n"));
+        assert!(dot.contains("  0 -> 1 [style=solid];\n"));
+        assert!(dot.contains("  1 -> 2 [style=solid];\n"));
+        assert!(dot.contains("  2 -> 3 [style=solid];\n"));
+        assert_eq!(dot.matches("->").count(), 3);
+    }
+
+    #[test]
+    fn test_draw_digraph_canonical_node_order() {
+        let orient = |graph: &UnGraph| {
+            draw_digraph(graph, |e| {
+                let (s, t) = graph.edge_endpoints(e).unwrap();
+                if graph[s] < graph[t] { (s, t) } else { (t, s) }
+            })
+        };
+
+        // the same labeled triangle, nodes are added in different orders
+        let graph = crate::input::from_str("10,20\n20,30\n30,10,v\n");
+        let permuted = crate::input::from_str("30,20\n10,30,v\n20,10\n");
+        assert_eq!(orient(&graph), orient(&permuted));
+        assert!(orient(&permuted).contains("  0 -> 2 [style=dashed];\n"));
+
+        // undirected and directed drawings number nodes the same way
+        assert!(draw_graph(&permuted).contains("  0 -- 2 [style=dashed];\n"));
+    }
+
+    #[test]
+    fn test_to_html_report() {
+        // envelope is biconnected, so there is one SPQR tree
+        let envelope = crate::input::from_file("assets/envelope.in");
+        let html = to_html_report(&envelope);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<svg").count(), 3);
+        assert_eq!(html.matches("</svg>").count(), 3);
+
+        // two triangles joined by a bridge: 3 blocks, but only 2 of them have SPQR trees
+        let graph = crate::input::from_str("0,1\n1,2\n2,0\n2,3\n3,4\n4,5\n5,3\n");
+        let html = to_html_report(&graph);
+        assert_eq!(html.matches("<svg").count(), 4);
+        assert_eq!(html.matches("<h1>SPQR tree").count(), 2);
+    }
+
+    #[test]
+    fn test_to_tikz() {
+        let mut graph = UnGraph::new_undirected();
+        let a = graph.add_node(7);
+        let b = graph.add_node(8);
+        let c = graph.add_node(9);
+        graph.add_edge(a, b, EdgeLabel::Real);
+        graph.add_edge(b, c, EdgeLabel::Virtual);
+        let pos = [(0.0, 0.0), (1.0, 0.0), (0.5, 1.0)];
+
+        let tikz = to_tikz(&graph, &pos);
+        assert!(tikz.starts_with("\\begin{tikzpicture}\n"));
+        assert!(tikz.ends_with("\\end{tikzpicture}\n"));
+        assert_eq!(tikz.matches("\\node").count(), graph.node_count());
+        assert!(tikz.contains("(v2) at (0.500, 1.000) {9};"));
+        assert!(tikz.contains("  \\draw[dashed] (v1) -- (v2);\n"));
+    }
+
+    #[test]
+    fn test_to_edge_list_round_trip() {
+        use petgraph::algo::is_isomorphic_matching;
+
+        let graph = crate::input::from_str("5,3\n3,7\n7,5\n5,3\n7,9,v\n");
+        let text = to_edge_list(&graph);
+        assert_eq!(text, "3,5\n3,5\n3,7\n5,7\n7,9,v\n");
+
+        let reread = crate::input::from_str(&text);
+        assert!(is_isomorphic_matching(
+            &graph,
+            &reread,
+            |a, b| a == b,
+            |a, b| a == b
+        ));
+    }
+}
@@ -0,0 +1,343 @@
+use crate::collections::HashMap;
+use crate::{
+    embedding::is_planar, spqr_blocks::outside_structures::SPQRTree,
+    triconnected_blocks::outside_structures::ComponentType,
+};
+
+// labels of edges in codes of skeletons, virtual edges are labeled with hashes of the subtrees behind them
+const REAL: u64 = 0;
+const PARENT: u64 = 1;
+
+/// Neighbors of every vertex of a skeleton in cyclic order and edges of the skeleton by their endpoints.
+type SkeletonRotation = (HashMap<usize, Vec<usize>>, HashMap<(usize, usize), usize>);
+
+impl SPQRTree {
+    /// Returns a hash of the SPQR tree which doesn't depend on numbering of components or vertices.
+    ///
+    /// The tree is rooted at its center (at the virtual edge between the two central components if there are two)
+    /// and every component is given a canonical code of its skeleton, bottom-up:
+    /// - a virtual edge is labeled with the code of the subtree behind it, taken for the direction in which
+    ///   the edge is traversed, so it is known which pole of the subtree is glued to which vertex,
+    /// - S-nodes are coded by the labels met walking around the cycle,
+    /// - P-nodes by the sorted labels of edges between their poles,
+    /// - planar R-nodes by a traversal of their embedding, which is unique up to a mirror image,
+    ///   started from every half-edge (or from the one to the parent), in both orientations.
+    ///
+    /// Codes are hashed with FxHash, which is fixed, so hashes are the same in every run and on every platform.
+    ///
+    /// Isomorphic graphs get the same hash. When all R-nodes are planar, codes describe the graph up to isomorphism,
+    /// so graphs with equal hashes are isomorphic unless two 64-bit hashes happen to be equal.
+    /// Non-planar R-nodes and components made by [`SPQRTree::merge_adjacent`] are coded only by their size,
+    /// degrees and labels of their edges, so different graphs can share the hash through them.
+    pub fn canonical_hash(&self) -> u64 {
+        let mut edge_components = vec![Vec::new(); self.blocks.edges.len()];
+        for (c, comp) in self.blocks.comp.iter().enumerate() {
+            for &eid in &comp.edges {
+                edge_components[eid].push(c);
+            }
+        }
+        let coder = SkeletonCoder {
+            tree: self,
+            edge_components,
+        };
+
+        let code = match self.center()[..] {
+            [c] => vec![coder.hashes(c, None)[0]],
+            [a, b] => {
+                let shared = *self.blocks.comp[a]
+                    .edges
+                    .iter()
+                    .find(|&&eid| {
+                        !self.blocks.is_real[eid] && self.blocks.comp[b].edges.contains(&eid)
+                    })
+                    .expect("Adjacent components share a virtual edge");
+                let (ha, hb) = (coder.hashes(a, Some(shared)), coder.hashes(b, Some(shared)));
+                // both sides are glued along the same direction of the shared edge
+                (0..2)
+                    .map(|o| {
+                        let mut pair = vec![ha[o], hb[o]];
+                        pair.sort();
+                        pair
+                    })
+                    .min()
+                    .unwrap()
+            }
+            _ => vec![],
+        };
+        fx_hash(&code)
+    }
+}
+
+/// FxHash of a code, it's a fixed function, unlike `DefaultHasher`, and it doesn't depend on endianness.
+fn fx_hash(code: &[u64]) -> u64 {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+    let mut hash = 0u64;
+    for &x in std::iter::once(&(code.len() as u64)).chain(code) {
+        hash = (hash.rotate_left(5) ^ x).wrapping_mul(SEED);
+    }
+    hash
+}
+
+/// Returns `k` such that `s` rotated left by `k` is its lexicographically smallest rotation, using Booth's algorithm in O(|s|).
+fn least_rotation(s: &[u64]) -> usize {
+    let n = s.len();
+    let at = |i: usize| s[i % n];
+    // failure function of the smallest rotation found so far, -1 for none
+    let mut f = vec![-1isize; 2 * n];
+    let mut k = 0;
+    for j in 1..2 * n {
+        let mut i = f[j - k - 1];
+        while i != -1 && at(j) != at(k + i as usize + 1) {
+            if at(j) < at(k + i as usize + 1) {
+                k = j - i as usize - 1;
+            }
+            i = f[i as usize];
+        }
+        if i == -1 && at(j) != at(k) {
+            if at(j) < at(k) {
+                k = j;
+            }
+            f[j - k] = -1;
+        } else {
+            f[j - k] = i + 1;
+        }
+    }
+    k % n
+}
+
+/// Computes canonical codes of skeletons, see [`SPQRTree::canonical_hash`].
+struct SkeletonCoder<'a> {
+    tree: &'a SPQRTree,
+    edge_components: Vec<Vec<usize>>,
+}
+
+impl SkeletonCoder<'_> {
+    /// Returns hashes of the subtree of component `c` hanging from the virtual edge `parent`, for both
+    /// directions of `parent`: from `edges[parent].0` to `edges[parent].1` and back.
+    ///
+    /// For the root (`parent` is `None`) both hashes are equal.
+    fn hashes(&self, c: usize, parent: Option<usize>) -> [u64; 2] {
+        let tricon = &self.tree.blocks;
+        let comp = &tricon.comp[c];
+
+        let children: HashMap<usize, [u64; 2]> = comp
+            .edges
+            .iter()
+            .filter(|&&eid| !tricon.is_real[eid] && Some(eid) != parent)
+            .map(|&eid| {
+                let &d = self.edge_components[eid].iter().find(|&&d| d != c).unwrap();
+                (eid, self.hashes(d, Some(eid)))
+            })
+            .collect();
+        let label = |eid: usize, from: usize| {
+            if Some(eid) == parent {
+                PARENT
+            } else if tricon.is_real[eid] {
+                REAL
+            } else if tricon.edges[eid].0 == from {
+                children[&eid][0]
+            } else {
+                children[&eid][1]
+            }
+        };
+        let other = |eid: usize, v: usize| {
+            let (s, t) = tricon.edges[eid];
+            if s == v { t } else { s }
+        };
+
+        let rotation = match comp.comp_type {
+            ComponentType::R => self.planar_rotation(c),
+            _ => None,
+        };
+        // edges around every vertex of a cycle, so walking around it takes O(1) per step
+        let mut incident: HashMap<usize, Vec<usize>> = HashMap::new();
+        if comp.comp_type == ComponentType::S {
+            for &eid in &comp.edges {
+                let (s, t) = tricon.edges[eid];
+                incident.entry(s).or_default().push(eid);
+                incident.entry(t).or_default().push(eid);
+            }
+        }
+        let code = |eid: usize, from: usize| {
+            let mut code = vec![comp.comp_type as u64 + 2];
+            match (comp.comp_type, &rotation) {
+                (ComponentType::S, _) => {
+                    // walk around the cycle starting with `from -> other(eid, from)`
+                    let (mut e, mut v) = (eid, from);
+                    loop {
+                        code.push(label(e, v));
+                        v = other(e, v);
+                        e = *incident[&v].iter().find(|&&f| f != e).unwrap();
+                        if e == eid {
+                            break;
+                        }
+                    }
+                }
+                (ComponentType::P, _) => {
+                    let mut labels: Vec<u64> = comp.edges.iter().map(|&e| label(e, from)).collect();
+                    labels.sort();
+                    code.extend(labels);
+                }
+                (_, Some((rotation, edge_between))) => {
+                    let traversal = |mirror: bool| {
+                        // vertices are numbered in the order of discovery, neighbors are scanned around
+                        // every vertex starting from the one it was discovered from
+                        let mut num = HashMap::new();
+                        let mut order = vec![from];
+                        let mut discovered_from = HashMap::new();
+                        num.insert(from, 0);
+                        discovered_from.insert(from, other(eid, from));
+
+                        let mut traversal = Vec::new();
+                        let mut i = 0;
+                        while i < order.len() {
+                            let w = order[i];
+                            i += 1;
+                            let mut rot: Vec<usize> = rotation[&w].clone();
+                            if mirror {
+                                rot.reverse();
+                            }
+                            let pos = rot.iter().position(|&x| x == discovered_from[&w]).unwrap();
+                            rot.rotate_left(pos);
+
+                            traversal.push(rot.len() as u64);
+                            for x in rot {
+                                if !num.contains_key(&x) {
+                                    num.insert(x, order.len() as u64);
+                                    discovered_from.insert(x, w);
+                                    order.push(x);
+                                }
+                                traversal.push(num[&x]);
+                                traversal.push(label(edge_between[&(w, x)], w));
+                            }
+                        }
+                        traversal
+                    };
+                    code.extend(traversal(false).min(traversal(true)));
+                }
+                _ => {
+                    // not planar or not canonical, only invariants of the skeleton are used
+                    let mut degree = HashMap::new();
+                    let mut labels = Vec::new();
+                    for &e in &comp.edges {
+                        let (s, t) = tricon.edges[e];
+                        *degree.entry(s).or_insert(0u64) += 1;
+                        *degree.entry(t).or_insert(0u64) += 1;
+                        labels.push(label(e, s).min(label(e, t)));
+                    }
+                    let mut degrees: Vec<u64> = degree.into_values().collect();
+                    degrees.sort();
+                    labels.sort();
+                    code.push(degrees.len() as u64);
+                    code.extend(degrees);
+                    code.extend(labels);
+                }
+            }
+            code
+        };
+
+        match parent {
+            Some(eid) => {
+                let (s, t) = tricon.edges[eid];
+                [fx_hash(&code(eid, s)), fx_hash(&code(eid, t))]
+            }
+            None => {
+                let (first, (s, t)) = (comp.edges[0], tricon.edges[comp.edges[0]]);
+                let best = match (comp.comp_type, &rotation) {
+                    (ComponentType::S, _) => {
+                        // walks from other edges are rotations of one of the two walks from the first edge
+                        [code(first, s), code(first, t)]
+                            .into_iter()
+                            .map(|mut code| {
+                                let k = least_rotation(&code[1..]);
+                                code[1..].rotate_left(k);
+                                code
+                            })
+                            .min()
+                            .unwrap()
+                    }
+                    (ComponentType::R, Some(_)) => comp
+                        .edges
+                        .iter()
+                        .flat_map(|&eid| {
+                            let (s, t) = tricon.edges[eid];
+                            [code(eid, s), code(eid, t)]
+                        })
+                        .min()
+                        .unwrap(),
+                    // codes of P-nodes depend only on the pole they start from, other codes don't depend on the start
+                    _ => code(first, s).min(code(first, t)),
+                };
+                [fx_hash(&best); 2]
+            }
+        }
+    }
+
+    /// Returns the rotation system of the skeleton of `c` (neighbors of every vertex in cyclic order)
+    /// and its edges by endpoints, or `None` if the skeleton is not planar.
+    ///
+    /// Skeletons of R-nodes are simple, so an edge is identified by its endpoints.
+    fn planar_rotation(&self, c: usize) -> Option<SkeletonRotation> {
+        let tricon = &self.tree.blocks;
+        let comp = &tricon.comp[c];
+
+        let (planar, embedding) = is_planar(&self.tree.skeleton_graph(c), false);
+        if !planar {
+            return None;
+        }
+
+        // nodes of the skeleton are vertices of the component in increasing order
+        let mut vertices: Vec<usize> = comp
+            .edges
+            .iter()
+            .flat_map(|&eid| [tricon.edges[eid].0, tricon.edges[eid].1])
+            .collect();
+        vertices.sort();
+        vertices.dedup();
+
+        let rotation = embedding
+            .node_indices()
+            .map(|x| {
+                let order = embedding
+                    .neighbors(x)
+                    .map(|y| vertices[y.index()])
+                    .collect();
+                (vertices[x.index()], order)
+            })
+            .collect();
+        let mut edge_between = HashMap::new();
+        for &eid in &comp.edges {
+            let (s, t) = tricon.edges[eid];
+            edge_between.insert((s, t), eid);
+            edge_between.insert((t, s), eid);
+        }
+        Some((rotation, edge_between))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_least_rotation() {
+        for seed in 0..500u64 {
+            // short sequences over a small alphabet, so there are many ties and periodic ones
+            let len = 1 + (seed % 9) as usize;
+            let s: Vec<u64> = (0..len as u64)
+                .map(|i| (seed * 7 + i * i * 3) % 3)
+                .collect();
+            let brute = (0..len)
+                .map(|k| {
+                    let mut r = s.clone();
+                    r.rotate_left(k);
+                    r
+                })
+                .min()
+                .unwrap();
+            let mut r = s.clone();
+            r.rotate_left(least_rotation(&s));
+            assert_eq!(r, brute, "{:?}", s);
+        }
+    }
+}
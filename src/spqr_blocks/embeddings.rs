@@ -0,0 +1,364 @@
+use crate::collections::HashMap;
+use crate::{
+    embedding::is_planar, spqr_blocks::outside_structures::SPQRTree,
+    triconnected_blocks::outside_structures::ComponentType,
+};
+
+impl SPQRTree {
+    /// Enumerates all combinatorial embeddings of the (biconnected) graph, as rotation systems.
+    ///
+    /// Every embedding is obtained by choosing the cyclic order of edges of each P-node
+    /// (`(k-1)!` choices for `k` edges) and the reflection of each R-node (`2` choices), S-nodes have no choices.
+    /// Embeddings of skeletons are then glued along virtual edges.
+    ///
+    /// `rotation[v]` lists neighbors of the vertex with internal index `v` in cyclic order,
+    /// the format accepted e.g. by [`crate::types::embed_with_rotation`].
+    ///
+    /// The number of embeddings is the one of
+    /// [`count_combinatorial_embeddings_biconnected`](crate::example_usages::combinatorial_embeddings::count_combinatorial_embeddings_biconnected),
+    /// so it grows factorially with sizes of P-nodes and exponentially with the number of R-nodes.
+    /// Embeddings are generated lazily, one at a time, but going through all of them is feasible only for small graphs.
+    ///
+    /// Yields nothing if the graph is not planar.
+    /// Panics if the tree contains components made by [`SPQRTree::merge_adjacent`].
+    ///
+    /// # Overflow
+    /// Embeddings are numbered with `u128`, so their number has to fit in it: a single P-node may have
+    /// at most 35 edges (`34!` orders) and big P-nodes and R-nodes together can't exceed `u128::MAX` choices.
+    /// Otherwise [`TooManyEmbeddings`] is returned for a planar graph, the same limit as the one of
+    /// [`count_combinatorial_embeddings`](crate::example_usages::combinatorial_embeddings::count_combinatorial_embeddings).
+    pub fn enumerate_embeddings(
+        &self,
+    ) -> Result<impl Iterator<Item = Vec<Vec<usize>>> + '_, TooManyEmbeddings> {
+        let n = self.labels.len();
+        let tricon = &self.blocks;
+        assert!(
+            tricon
+                .comp
+                .iter()
+                .all(|c| c.comp_type != ComponentType::MERGED),
+            "Tree must not contain merged components"
+        );
+
+        let mut edge_components = vec![Vec::new(); tricon.edges.len()];
+        let mut vertex_component = vec![None; n];
+        for (c, comp) in tricon.comp.iter().enumerate() {
+            for &eid in &comp.edges {
+                edge_components[eid].push(c);
+                let (s, t) = tricon.edges[eid];
+                vertex_component[s] = Some(c);
+                vertex_component[t] = Some(c);
+            }
+        }
+
+        // rotations of R-node skeletons, edges around every vertex of the skeleton
+        let mut r_rotations = HashMap::new();
+        let mut planar = true;
+        for (c, comp) in tricon.comp.iter().enumerate() {
+            if comp.comp_type != ComponentType::R {
+                continue;
+            }
+
+            let mut vertices: Vec<usize> = comp
+                .edges
+                .iter()
+                .flat_map(|&eid| [tricon.edges[eid].0, tricon.edges[eid].1])
+                .collect();
+            vertices.sort();
+            vertices.dedup();
+
+            let (is_planar, embedding) = is_planar(&self.skeleton_graph(c), false);
+            if !is_planar {
+                planar = false;
+                break;
+            }
+
+            // R-node skeletons are simple, so an edge is identified by its endpoints
+            let mut edge_between = HashMap::new();
+            for &eid in &comp.edges {
+                let (s, t) = tricon.edges[eid];
+                edge_between.insert((s, t), eid);
+                edge_between.insert((t, s), eid);
+            }
+            let rotation: HashMap<usize, Vec<usize>> = embedding
+                .node_indices()
+                .map(|x| {
+                    let v = vertices[x.index()];
+                    let order = embedding
+                        .neighbors(x)
+                        .map(|y| edge_between[&(v, vertices[y.index()])])
+                        .collect();
+                    (v, order)
+                })
+                .collect();
+            r_rotations.insert(c, rotation);
+        }
+
+        // a non-planar graph has no embeddings, however big its P-nodes are
+        let (radix, total) = if planar {
+            let radix: Vec<u128> = tricon
+                .comp
+                .iter()
+                .map(|comp| match comp.comp_type {
+                    ComponentType::P => {
+                        (1..comp.edges.len() as u128).try_fold(1u128, |acc, i| acc.checked_mul(i))
+                    }
+                    ComponentType::R => Some(2),
+                    _ => Some(1),
+                })
+                .collect::<Option<_>>()
+                .ok_or(TooManyEmbeddings)?;
+            let total = radix
+                .iter()
+                .try_fold(1u128, |acc, &r| acc.checked_mul(r))
+                .ok_or(TooManyEmbeddings)?;
+            (radix, total)
+        } else {
+            (Vec::new(), 0)
+        };
+
+        Ok((0..total).map(move |mut index| {
+            let choices: Vec<u128> = radix
+                .iter()
+                .map(|&r| {
+                    let choice = index % r;
+                    index /= r;
+                    choice
+                })
+                .collect();
+
+            let local = LocalRotations {
+                tree: self,
+                choices: &choices,
+                r_rotations: &r_rotations,
+                edge_components: &edge_components,
+            };
+
+            (0..n)
+                .map(|v| {
+                    let mut order = Vec::new();
+                    match vertex_component[v] {
+                        Some(c) => local.expand(c, v, None, &mut order),
+                        // a single edge has no components
+                        None => order.extend(
+                            (0..tricon.edges.len())
+                                .filter(|&e| tricon.edges[e].0 == v || tricon.edges[e].1 == v),
+                        ),
+                    }
+                    order
+                        .into_iter()
+                        .map(|eid| {
+                            let (s, t) = tricon.edges[eid];
+                            if s == v { t } else { s }
+                        })
+                        .collect()
+                })
+                .collect()
+        }))
+    }
+}
+
+/// The number of embeddings doesn't fit in `u128`, see [`SPQRTree::enumerate_embeddings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooManyEmbeddings;
+
+impl std::fmt::Display for TooManyEmbeddings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The number of embeddings doesn't fit in u128")
+    }
+}
+
+impl std::error::Error for TooManyEmbeddings {}
+
+/// Embeddings of skeletons chosen for a single embedding of the whole graph.
+struct LocalRotations<'a> {
+    tree: &'a SPQRTree,
+    choices: &'a [u128],
+    r_rotations: &'a HashMap<usize, HashMap<usize, Vec<usize>>>,
+    edge_components: &'a [Vec<usize>],
+}
+
+impl LocalRotations<'_> {
+    /// Returns edges of component `c` around its vertex `v`, in cyclic order.
+    fn around(&self, c: usize, v: usize) -> Vec<usize> {
+        let tricon = &self.tree.blocks;
+        let comp = &tricon.comp[c];
+        match comp.comp_type {
+            ComponentType::P => {
+                // the first edge stays in place, the choice is a permutation of the rest (Lehmer code)
+                let mut rest = comp.edges[1..].to_vec();
+                let mut order = vec![comp.edges[0]];
+                let mut code = self.choices[c];
+                while !rest.is_empty() {
+                    // doesn't overflow, it divides the radix of the P-node
+                    let fact: u128 = (1..rest.len() as u128).product();
+                    order.push(rest.remove((code / fact) as usize));
+                    code %= fact;
+                }
+                // going around the other pole, the edges are met in the reversed order
+                if tricon.edges[comp.edges[0]].0 != v {
+                    order.reverse();
+                }
+                order
+            }
+            ComponentType::R => {
+                let mut order = self.r_rotations[&c][&v].clone();
+                if self.choices[c] == 1 {
+                    order.reverse();
+                }
+                order
+            }
+            _ => comp
+                .edges
+                .iter()
+                .copied()
+                .filter(|&e| tricon.edges[e].0 == v || tricon.edges[e].1 == v)
+                .collect(),
+        }
+    }
+
+    /// Appends real edges around `v` in component `c` and, recursively, in components glued to it
+    /// along virtual edges, skipping `from` - the virtual edge we came through.
+    fn expand(&self, c: usize, v: usize, from: Option<usize>, out: &mut Vec<usize>) {
+        let mut order = self.around(c, v);
+        if let Some(from) = from {
+            let pos = order.iter().position(|&e| e == from).unwrap();
+            order.rotate_left(pos + 1);
+            order.pop();
+        }
+
+        for eid in order {
+            if self.tree.blocks.is_real[eid] {
+                out.push(eid);
+            } else {
+                let &next = self.edge_components[eid].iter().find(|&&d| d != c).unwrap();
+                self.expand(next, v, Some(eid), out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::HashSet;
+    use crate::{
+        UnGraph,
+        drawing_blocks::faces::get_faces,
+        embedding::is_planar_bool,
+        example_usages::combinatorial_embeddings::count_combinatorial_embeddings_biconnected,
+        spqr_tree::get_spqr_tree,
+        testing::random_graphs::random_biconnected_graph,
+        types::{edge_multiplicity, embed_with_rotation},
+    };
+
+    // rotations starting from the smallest neighbor, so that equal embeddings are equal
+    fn canonical(mut rotation: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+        for order in rotation.iter_mut() {
+            if let Some(pos) = order
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, &x)| x)
+                .map(|(i, _)| i)
+            {
+                order.rotate_left(pos);
+            }
+        }
+        rotation
+    }
+
+    fn check_embeddings(graph: &UnGraph) {
+        let embeddings: Vec<_> = get_spqr_tree(graph)
+            .enumerate_embeddings()
+            .unwrap()
+            .collect();
+        assert_eq!(
+            embeddings.len() as u128,
+            count_combinatorial_embeddings_biconnected(graph)
+        );
+
+        let simple = graph.edge_indices().all(|e| {
+            let (a, b) = graph.edge_endpoints(e).unwrap();
+            edge_multiplicity(graph, graph[a], graph[b]) == 1
+        });
+        if simple {
+            let distinct: HashSet<_> = embeddings.iter().cloned().map(canonical).collect();
+            assert_eq!(distinct.len(), embeddings.len());
+
+            // Euler's formula holds only for planar rotation systems
+            for rotation in &embeddings {
+                let faces = get_faces(&embed_with_rotation(graph, rotation)).len();
+                assert_eq!(graph.node_count() + faces, graph.edge_count() + 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_enumerate_embeddings_envelope() {
+        let envelope = crate::input::from_file("assets/envelope.in");
+        check_embeddings(&envelope);
+        assert_eq!(
+            get_spqr_tree(&envelope)
+                .enumerate_embeddings()
+                .unwrap()
+                .count(),
+            16
+        );
+
+        // diagonals of the square make an R-node
+        let mut graph = envelope.clone();
+        graph.add_edge(0.into(), 2.into(), crate::EdgeLabel::Real);
+        graph.add_edge(1.into(), 3.into(), crate::EdgeLabel::Real);
+        check_embeddings(&graph);
+    }
+
+    #[test]
+    fn test_enumerate_embeddings_light() {
+        for i in 0..100 {
+            let n = 2 + i / 20;
+            let graph = random_biconnected_graph(n, 1 + i / 4, i);
+            // big bonds of multigraphs have too many embeddings to go through
+            if is_planar_bool(&graph) && count_combinatorial_embeddings_biconnected(&graph) <= 1000
+            {
+                check_embeddings(&graph);
+            }
+        }
+
+        // K5 is not planar
+        let k5 = crate::input::from_str("0,1\n0,2\n0,3\n0,4\n1,2\n1,3\n1,4\n2,3\n2,4\n3,4\n");
+        assert_eq!(
+            get_spqr_tree(&k5).enumerate_embeddings().unwrap().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_enumerate_embeddings_overflow() {
+        let bond = |k: usize| {
+            let mut graph = UnGraph::new_undirected();
+            let (a, b) = (graph.add_node(0), graph.add_node(1));
+            for _ in 0..k {
+                graph.add_edge(a, b, crate::EdgeLabel::Real);
+            }
+            graph
+        };
+
+        // 34! orders of edges fit in u128, 35! don't
+        let spqr_tree = get_spqr_tree(&bond(35));
+        let mut embeddings = spqr_tree.enumerate_embeddings().unwrap();
+        assert_eq!(embeddings.next().unwrap()[0].len(), 35);
+        assert_eq!(
+            get_spqr_tree(&bond(36)).enumerate_embeddings().err(),
+            Some(super::TooManyEmbeddings)
+        );
+
+        // K5 with a big bond is not planar, so there is nothing to count
+        let mut k5 = crate::input::from_str("0,1\n0,2\n0,3\n0,4\n1,2\n1,3\n1,4\n2,3\n2,4\n3,4\n");
+        for _ in 0..40 {
+            k5.add_edge(0.into(), 1.into(), crate::EdgeLabel::Real);
+        }
+        assert_eq!(
+            get_spqr_tree(&k5).enumerate_embeddings().unwrap().count(),
+            0
+        );
+    }
+}
@@ -1,2 +1,4 @@
+pub mod canonical;
+pub mod embeddings;
 pub mod outside_structures;
 pub mod visualize;
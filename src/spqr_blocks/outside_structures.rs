@@ -1,27 +1,409 @@
-use crate::triconnected_blocks::outside_structures::TriconnectedComponents;
+use crate::{
+    EdgeLabel, UnGraph,
+    drawing_blocks::{
+        schnyder::{DrawingResult, draw},
+        triangulate::triangulate,
+    },
+    embedding::is_planar_bool,
+    triconnected_blocks::outside_structures::{ComponentType, TriconnectedComponents},
+};
+use petgraph::graph::NodeIndex;
+use std::collections::VecDeque;
 
 /// Represents the SPQR tree structure built from triconnected components.
 ///
 /// - Vertices are numbered from `0` to `k-1`, where `k` is the number of triconnected components.
 /// - `adj[u]` contains the indices of components adjacent to component `u` in the SPQR tree.
-#[derive(Debug, Clone)]
+/// - `labels[v]` is the label of the vertex with internal index `v` in the input graph,
+///   as components refer to vertices by internal indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SPQRTree {
     pub blocks: TriconnectedComponents,
     pub adj: Vec<Vec<usize>>,
+    pub labels: Vec<u32>,
 }
 
 impl SPQRTree {
+    /// Creates SPQR tree without edges. Vertices are labeled with their internal indices.
     pub fn new(triconnected_components: &TriconnectedComponents) -> Self {
         let n = triconnected_components.comp.len();
         let adj = vec![Vec::new(); n];
+        let n_verts = triconnected_components
+            .edges
+            .iter()
+            .map(|(a, b)| a.max(b) + 1)
+            .max()
+            .unwrap_or(0);
         SPQRTree {
             blocks: triconnected_components.clone(),
             adj,
+            labels: (0..n_verts as u32).collect(),
+        }
+    }
+
+    /// Returns labels of vertices of the input graph contained in component `node`, in increasing order.
+    pub fn component_vertices(&self, node: usize) -> Vec<u32> {
+        let mut vertices: Vec<u32> = self.blocks.comp[node]
+            .edges
+            .iter()
+            .flat_map(|&eid| {
+                let (s, t) = self.blocks.edges[eid];
+                [self.labels[s], self.labels[t]]
+            })
+            .collect();
+        vertices.sort();
+        vertices.dedup();
+        vertices
+    }
+
+    /// Returns the skeleton of component `node`: its real and virtual edges, labeled accordingly.
+    ///
+    /// Nodes are vertices of the component in increasing order of their internal indices
+    /// in the input graph, labeled with `labels`.
+    pub fn skeleton_graph(&self, node: usize) -> UnGraph {
+        let comp_edges = &self.blocks.comp[node].edges;
+        let mut vertices: Vec<usize> = comp_edges
+            .iter()
+            .flat_map(|&eid| {
+                let (s, t) = self.blocks.edges[eid];
+                [s, t]
+            })
+            .collect();
+        vertices.sort();
+        vertices.dedup();
+
+        let mut graph = UnGraph::new_undirected();
+        for &v in &vertices {
+            graph.add_node(self.labels[v]);
         }
+
+        let to_internal = |v: usize| NodeIndex::new(vertices.binary_search(&v).unwrap());
+        for &eid in comp_edges {
+            let (s, t) = self.blocks.edges[eid];
+            let label = if self.blocks.is_real[eid] {
+                EdgeLabel::Real
+            } else {
+                EdgeLabel::Virtual
+            };
+            graph.add_edge(to_internal(s), to_internal(t), label);
+        }
+
+        graph
+    }
+
+    /// Draws the skeleton of R-node `node` with Schnyder's algorithm after triangulating it.
+    ///
+    /// R-node skeletons are triconnected, so a planar one has a unique embedding up to reflection.
+    /// `coordinates[i]` is the position of node `i` of [`SPQRTree::skeleton_graph`].
+    ///
+    /// Returns `None` if `node` is not an R-node or its skeleton is not planar.
+    pub fn draw_r_node(&self, node: usize) -> Option<DrawingResult> {
+        if self.blocks.comp[node].comp_type != ComponentType::R {
+            return None;
+        }
+
+        let skeleton = self.skeleton_graph(node);
+        if !is_planar_bool(&skeleton) {
+            return None;
+        }
+        Some(draw(&triangulate(&skeleton)))
+    }
+
+    /// Returns `true` iff `adj` forms a tree: it is connected and has exactly `components - 1` edges.
+    ///
+    /// Tree with no components is a tree as well.
+    pub fn is_tree(&self) -> bool {
+        if self.adj.is_empty() {
+            return true;
+        }
+
+        let degree_sum: usize = self.adj.iter().map(|l| l.len()).sum();
+        let (dist, _) = self.bfs(0);
+        degree_sum == 2 * (self.adj.len() - 1) && dist.iter().all(|&d| d != usize::MAX)
     }
-    pub(crate) fn add_edge(&mut self, u: usize, v: usize) {
-        self.adj[u].push(v);
-        self.adj[v].push(u);
+
+    /// Returns BFS distances from `start` and parents in the BFS tree.
+    fn bfs(&self, start: usize) -> (Vec<usize>, Vec<Option<usize>>) {
+        let mut dist = vec![usize::MAX; self.adj.len()];
+        let mut parent = vec![None; self.adj.len()];
+        let mut queue = VecDeque::new();
+
+        dist[start] = 0;
+        queue.push_back(start);
+        while let Some(u) = queue.pop_front() {
+            for &to in &self.adj[u] {
+                if dist[to] == usize::MAX {
+                    dist[to] = dist[u] + 1;
+                    parent[to] = Some(u);
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        (dist, parent)
+    }
+
+    /// Returns the longest path in the tree as a list of components, found with two BFS passes.
+    fn longest_path(&self) -> Vec<usize> {
+        if self.adj.is_empty() {
+            return vec![];
+        }
+
+        let farthest = |dist: &Vec<usize>| (0..dist.len()).max_by_key(|&u| dist[u]).unwrap();
+
+        let (dist, _) = self.bfs(0);
+        let a = farthest(&dist);
+        let (dist, parent) = self.bfs(a);
+        let b = farthest(&dist);
+
+        let mut path = vec![b];
+        while let Some(p) = parent[*path.last().unwrap()] {
+            path.push(p);
+        }
+        path
+    }
+
+    /// Returns the diameter of the SPQR tree, i.e. the number of tree edges on its longest path.
+    ///
+    /// Tree with no components has diameter `0`.
+    pub fn diameter(&self) -> usize {
+        self.longest_path().len().saturating_sub(1)
+    }
+
+    /// Returns `true` if the (biconnected) graph is series-parallel, i.e. its SPQR tree has no R-nodes.
+    ///
+    /// Panics if the tree was coarsened by [`SPQRTree::merge_adjacent`] into a non-canonical skeleton.
+    pub fn is_series_parallel(&self) -> bool {
+        assert!(
+            self.blocks
+                .comp
+                .iter()
+                .all(|c| c.comp_type != ComponentType::MERGED),
+            "Tree must not contain merged components"
+        );
+        self.blocks
+            .comp
+            .iter()
+            .all(|c| c.comp_type != ComponentType::R)
+    }
+
+    /// Returns components of degree `1` in the SPQR tree, in increasing order.
+    ///
+    /// Leaves are S-nodes or P-nodes attached to the rest of the tree by a single virtual edge.
+    /// A tree with a single component has no leaves.
+    pub fn leaves(&self) -> Vec<usize> {
+        (0..self.adj.len())
+            .filter(|&c| self.adj[c].len() == 1)
+            .collect()
+    }
+
+    /// Returns the center of the SPQR tree: one component, or two adjacent components if the diameter is odd.
+    ///
+    /// Rooting the tree at the center minimizes its height.
+    pub fn center(&self) -> Vec<usize> {
+        let path = self.longest_path();
+        if path.is_empty() {
+            return vec![];
+        }
+
+        let d = path.len() - 1;
+        if d.is_multiple_of(2) {
+            vec![path[d / 2]]
+        } else {
+            vec![path[d / 2], path[d / 2 + 1]]
+        }
+    }
+
+    /// Merges adjacent components `a` and `b` into one skeleton by removing their shared virtual edge.
+    ///
+    /// Merging two cycles gives a cycle and merging two bonds gives a bond, any other merge is marked as
+    /// [`ComponentType::MERGED`], since the skeleton is neither a cycle nor a bond and it has a separation pair.
+    /// Queries relying on types of canonical components, e.g. [`SPQRTree::is_series_parallel`], reject such trees.
+    ///
+    /// The merged component gets index `min(a, b)` and the last component is moved to index `max(a, b)`,
+    /// other indices are unchanged, `blocks.to_split` is updated accordingly. Real edges of the tree stay the same.
+    pub fn merge_adjacent(&mut self, a: usize, b: usize) {
+        assert!(
+            self.adj[a].contains(&b),
+            "Components must be adjacent in the SPQR tree"
+        );
+        let (a, b) = (a.min(b), a.max(b));
+
+        let shared = *self.blocks.comp[a]
+            .edges
+            .iter()
+            .find(|&&eid| !self.blocks.is_real[eid] && self.blocks.comp[b].edges.contains(&eid))
+            .expect("Adjacent components share a virtual edge");
+
+        let removed = std::mem::take(&mut self.blocks.comp[b].edges);
+        let removed_type = self.blocks.comp[b].comp_type;
+        let merged = &mut self.blocks.comp[a];
+        merged.edges.retain(|&eid| eid != shared);
+        merged
+            .edges
+            .extend(removed.into_iter().filter(|&eid| eid != shared));
+        if merged.comp_type != removed_type || merged.comp_type == ComponentType::R {
+            merged.comp_type = ComponentType::MERGED;
+        }
+
+        // neighbors of `b` are now attached to `a`
+        let b_adj = std::mem::take(&mut self.adj[b]);
+        self.adj[a].retain(|&x| x != b);
+        for to in b_adj.into_iter().filter(|&to| to != a) {
+            for x in self.adj[to].iter_mut() {
+                if *x == b {
+                    *x = a;
+                }
+            }
+            self.adj[a].push(to);
+        }
+
+        // fill the hole at `b` with the last component
+        let last = self.adj.len() - 1;
+        self.blocks.comp.swap_remove(b);
+        self.adj.swap_remove(b);
+        for owner in self.blocks.to_split.iter_mut().flatten() {
+            if *owner == b {
+                *owner = a;
+            } else if *owner == last {
+                *owner = b;
+            }
+        }
+        if b != last {
+            for to in self.adj[b].clone() {
+                for x in self.adj[to].iter_mut() {
+                    if *x == last {
+                        *x = b;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the pertinent graph of component `toward` with respect to its neighbor `node`.
+    ///
+    /// It is the union of real edges of all components lying on the far side of the virtual edge
+    /// between `node` and `toward`, i.e. in the subtree of `toward` when the tree is rooted at `node`.
+    ///
+    /// Nodes are vertices of these edges in increasing order of their internal indices
+    /// in the input graph, labeled with `labels`.
+    pub fn pertinent_graph(&self, node: usize, toward: usize) -> UnGraph {
+        assert!(
+            self.adj[node].contains(&toward),
+            "Components must be adjacent in the SPQR tree"
+        );
+
+        let mut edges = Vec::new();
+        let mut stack = vec![(toward, node)];
+        while let Some((u, parent)) = stack.pop() {
+            for &eid in &self.blocks.comp[u].edges {
+                if self.blocks.is_real[eid] {
+                    edges.push(self.blocks.edges[eid]);
+                }
+            }
+
+            for &to in &self.adj[u] {
+                if to != parent {
+                    stack.push((to, u));
+                }
+            }
+        }
+
+        let mut vertices: Vec<usize> = edges.iter().flat_map(|&(s, t)| [s, t]).collect();
+        vertices.sort();
+        vertices.dedup();
+
+        let mut graph = UnGraph::new_undirected();
+        for &v in &vertices {
+            graph.add_node(self.labels[v]);
+        }
+
+        let to_internal = |v: usize| NodeIndex::new(vertices.binary_search(&v).unwrap());
+        for &(s, t) in &edges {
+            graph.add_edge(to_internal(s), to_internal(t), EdgeLabel::Real);
+        }
+
+        graph
+    }
+
+    /// Returns the input graph with the split structure overlaid: all real edges
+    /// and one copy of every virtual edge, labeled `Virtual`.
+    ///
+    /// Nodes keep internal indices of the input graph and are labeled with `labels`.
+    pub fn expansion_graph(&self) -> UnGraph {
+        let mut graph = UnGraph::new_undirected();
+        for &label in &self.labels {
+            graph.add_node(label);
+        }
+
+        let mut seen = vec![false; self.blocks.edges.len()];
+        for comp in &self.blocks.comp {
+            for &eid in &comp.edges {
+                if seen[eid] {
+                    continue;
+                }
+                seen[eid] = true;
+
+                let (s, t) = self.blocks.edges[eid];
+                let label = if self.blocks.is_real[eid] {
+                    EdgeLabel::Real
+                } else {
+                    EdgeLabel::Virtual
+                };
+                graph.add_edge(NodeIndex::new(s), NodeIndex::new(t), label);
+            }
+        }
+
+        graph
+    }
+
+    /// Renders the SPQR tree rooted at component `0` as text, similarly to the `tree` command.
+    ///
+    /// Each line describes one component: its type, index and number of edges in its skeleton, e.g.
+    /// ```text
+    /// S0 (4 edges)
+    /// ├── P1 (3 edges)
+    /// │   └── S2 (3 edges)
+    /// └── P3 (3 edges)
+    /// ```
+    pub fn print_ascii(&self) -> String {
+        fn print(tree: &SPQRTree, u: usize, parent: Option<usize>, prefix: &str, out: &mut String) {
+            let children: Vec<usize> = tree.adj[u]
+                .iter()
+                .copied()
+                .filter(|&to| Some(to) != parent)
+                .collect();
+
+            for (i, &to) in children.iter().enumerate() {
+                let last = i + 1 == children.len();
+                let comp = &tree.blocks.comp[to];
+                out.push_str(&format!(
+                    "{}{}{}{} ({} edges)\n",
+                    prefix,
+                    if last { "└── " } else { "├── " },
+                    comp.comp_type,
+                    to,
+                    comp.edges.len()
+                ));
+                let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                print(tree, to, Some(u), &prefix, out);
+            }
+        }
+
+        let mut out = String::new();
+        if self.adj.is_empty() {
+            return out;
+        }
+
+        let root = &self.blocks.comp[0];
+        out.push_str(&format!(
+            "{}0 ({} edges)\n",
+            root.comp_type,
+            root.edges.len()
+        ));
+        print(self, 0, None, "", &mut out);
+
+        out
     }
 }
 
@@ -30,7 +412,7 @@ impl SPQRTree {
 /// - `allocation_node[u]`: Lowest component that contains a vertex 'u'.
 /// - `reference_edge[v]`: For a component `v`, it defines the vedge that is common between `v` and `parent(v)` in the SPQR tree.
 /// - `parent_node[v]`: Parent component of `v` in the SPQR tree
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RootedSPQRTree {
     pub blocks: TriconnectedComponents,
     pub adj: Vec<Vec<usize>>,
@@ -47,10 +429,9 @@ impl RootedSPQRTree {
             .blocks
             .edges
             .iter()
-            .map(|(a, b)| a.max(b))
+            .map(|(a, b)| a.max(b) + 1)
             .max()
-            .unwrap()
-            + 1;
+            .unwrap_or(0);
         RootedSPQRTree {
             blocks: spqr_tree.blocks.clone(),
             adj: spqr_tree.adj.clone(),
@@ -59,4 +440,36 @@ impl RootedSPQRTree {
             par_v: vec![None; n_comps],
         }
     }
+
+    /// Reroots the tree at component `new_root` in place.
+    ///
+    /// Only components on the path from `new_root` to the current root are touched,
+    /// so the cost is proportional to the total size of components along that path.
+    pub fn reroot(&mut self, new_root: usize) {
+        let mut path = vec![new_root];
+        while let Some(p) = self.par_v[*path.last().unwrap()] {
+            path.push(p);
+        }
+
+        // flip parent pointers along the path; the shared vedge moves to the former parent
+        for i in (0..path.len() - 1).rev() {
+            let (child, parent) = (path[i], path[i + 1]);
+            self.par_v[parent] = Some(child);
+            self.ref_edge[parent] = self.ref_edge[child];
+            self.adj[parent].retain(|&x| x != child);
+            self.adj[child].push(parent);
+        }
+        self.par_v[new_root] = None;
+        self.ref_edge[new_root] = None;
+
+        // the topmost component containing a vertex can only change to one on the path,
+        // and it is the one closest to the new root
+        for &c in path.iter().rev() {
+            for &eid in self.blocks.comp[c].edges.iter() {
+                let (a, b) = self.blocks.edges[eid];
+                self.alloc_node[a] = c;
+                self.alloc_node[b] = c;
+            }
+        }
+    }
 }
@@ -1,10 +1,12 @@
 use crate::{
     spqr_blocks::outside_structures::SPQRTree,
-    triconnected_blocks::visualize::visualize_triconnected,
+    triconnected_blocks::visualize::{component_prefix, visualize_triconnected},
 };
 
 /// Given a `SPQRTree` structure, this function generates a
 /// Graphviz DOT representation of the SPQR tree.
+///
+/// Components with a single real edge are rendered as Q-nodes.
 pub fn visualize_spqr(spqr: &SPQRTree) -> String {
     let mut output = visualize_triconnected(&spqr.blocks);
 
@@ -19,8 +21,7 @@ pub fn visualize_spqr(spqr: &SPQRTree) -> String {
             j += new_j + 3; // skip "  }"
             continue;
         }
-        let prefix = spqr.blocks.comp[i].comp_type;
-        let prefix = prefix.to_string();
+        let prefix = component_prefix(&spqr.blocks.comp[i], &spqr.blocks);
         let label = format!("{}{}_connector", prefix, i + 1);
 
         let write_str = format!(
@@ -45,11 +46,8 @@ pub fn visualize_spqr(spqr: &SPQRTree) -> String {
     for (u, adj_u) in spqr.adj.iter().enumerate() {
         for &v in adj_u {
             if u < v {
-                let u_type = spqr.blocks.comp[u].comp_type;
-                let v_type = spqr.blocks.comp[v].comp_type;
-
-                let u_prefix = u_type.to_string();
-                let v_prefix = v_type.to_string();
+                let u_prefix = component_prefix(&spqr.blocks.comp[u], &spqr.blocks);
+                let v_prefix = component_prefix(&spqr.blocks.comp[v], &spqr.blocks);
 
                 let u_label = format!("{}{}_connector", u_prefix, u + 1);
                 let v_label = format!("{}{}_connector", v_prefix, v + 1);
@@ -71,3 +69,108 @@ pub fn visualize_spqr(spqr: &SPQRTree) -> String {
 
     output
 }
+
+/// Works like [`visualize_spqr`], but highlights the vertex labeled `vertex`
+/// in the input graph and in every component containing it.
+///
+/// Panics if there is no vertex with such label.
+pub fn visualize_spqr_highlight(spqr: &SPQRTree, vertex: u32) -> String {
+    let v = spqr
+        .labels
+        .iter()
+        .position(|&l| l == vertex)
+        .expect("Vertex with given label should exist");
+
+    let mut output = visualize_spqr(spqr);
+    // drop the closing "}\n" and restyle copies of the vertex, attributes set later win
+    output.truncate(output.len() - 2);
+
+    output.push_str(&format!("  {} [fillcolor=\"orange\", penwidth=2];\n", v));
+    for (i, comp) in spqr.blocks.comp.iter().enumerate() {
+        let contains = comp.edges.iter().any(|&eid| {
+            let (s, t) = spqr.blocks.edges[eid];
+            s == v || t == v
+        });
+        if contains {
+            output.push_str(&format!(
+                "  {}{}_{} [fillcolor=\"orange\", penwidth=2];\n",
+                component_prefix(comp, &spqr.blocks),
+                i + 1,
+                v
+            ));
+        }
+    }
+
+    output.push_str("}\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::triconnected_blocks::outside_structures::{
+        Component, ComponentType, TriconnectedComponents,
+    };
+
+    #[test]
+    fn test_visualize_spqr_highlight_pole() {
+        let envelope = crate::input::from_file("assets/envelope.in");
+        let spqr = crate::spqr_tree::get_spqr_tree(&envelope);
+
+        // vertex 0 is a pole of the ears 0 - 4 - 3 and 0 - 5 - 1
+        let dot = visualize_spqr_highlight(&spqr, 0);
+        let highlighted: Vec<&str> = dot
+            .lines()
+            .filter(|l| l.contains("fillcolor=\"orange\""))
+            .collect();
+        let containing = (0..spqr.adj.len())
+            .filter(|&c| spqr.component_vertices(c).contains(&0))
+            .count();
+        assert!(containing >= 2);
+        // the vertex of the input graph and one copy per component
+        assert_eq!(highlighted.len(), containing + 1);
+        assert!(dot.trim_end().ends_with('}'));
+
+        // ear tip is only in one component
+        let dot = visualize_spqr_highlight(&spqr, 4);
+        assert_eq!(dot.matches("fillcolor=\"orange\"").count(), 2);
+    }
+
+    #[test]
+    fn test_visualize_spqr_q_leaves() {
+        // triangle 0 - 1 - 2 whose real edges hang off the cycle as single-edge leaves
+        let tricon = TriconnectedComponents {
+            comp: vec![
+                Component {
+                    edges: vec![3, 4, 5],
+                    comp_type: ComponentType::S,
+                },
+                Component {
+                    edges: vec![0],
+                    comp_type: ComponentType::P,
+                },
+                Component {
+                    edges: vec![1],
+                    comp_type: ComponentType::P,
+                },
+                Component {
+                    edges: vec![2],
+                    comp_type: ComponentType::P,
+                },
+            ],
+            edges: vec![(0, 1), (1, 2), (2, 0), (0, 1), (1, 2), (2, 0)],
+            is_real: vec![true, true, true, false, false, false],
+            to_split: vec![],
+        };
+        let mut spqr = SPQRTree::new(&tricon);
+        spqr.adj = vec![vec![1, 2, 3], vec![0], vec![0], vec![0]];
+
+        let dot = visualize_spqr(&spqr);
+        assert_eq!(dot.matches("subgraph cluster_Q").count(), 3);
+        assert_eq!(dot.matches("subgraph cluster_S").count(), 1);
+        assert_eq!(dot.matches("shape=box").count(), 6);
+        for q in 2..=4 {
+            assert!(dot.contains(&format!("S1_connector -- Q{}_connector", q)));
+        }
+    }
+}
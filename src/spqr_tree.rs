@@ -4,6 +4,7 @@ use crate::{
     UnGraph,
     spqr_blocks::outside_structures::{RootedSPQRTree, SPQRTree},
     triconnected::get_triconnected_components,
+    triconnected_blocks::outside_structures::TriconnectedComponents,
 };
 
 /// ## Overwiew
@@ -11,31 +12,30 @@ use crate::{
 ///
 /// For more information, refer to the triconnected.rs module documentation.
 ///
+/// A graph with a single vertex (and no edges) has no triconnected components,
+/// so for it an empty SPQR tree (no components, no edges) is returned.
+///
 /// ## Example (visualized using .dot file generated with visualize.rs from spqr_blocks)
 /// ![SPQR_Full][spqr_full]
 #[embed_doc_image("spqr_full", "assets/spqr_tree.svg")]
 pub fn get_spqr_tree(graph: &UnGraph) -> SPQRTree {
-    let triconnected_components = get_triconnected_components(graph);
+    let triconnected_components = if graph.node_count() <= 1 {
+        TriconnectedComponents {
+            comp: vec![],
+            edges: vec![],
+            is_real: vec![],
+            to_split: vec![],
+        }
+    } else {
+        get_triconnected_components(graph)
+    };
 
     let mut spqr_tree = SPQRTree::new(&triconnected_components);
+    spqr_tree.labels = graph.node_weights().copied().collect();
 
     // now we just add edges between components
-    let mut edge_to_component = vec![0; triconnected_components.edges.len()];
-    for (i, component) in triconnected_components.comp.iter().enumerate() {
-        for &eid in &component.edges {
-            edge_to_component[eid] = i;
-        }
-    }
-
-    for (i, component) in triconnected_components.comp.iter().enumerate() {
-        for &eid in &component.edges {
-            if edge_to_component[eid] == i {
-                continue;
-            }
-
-            spqr_tree.add_edge(i, edge_to_component[eid]);
-        }
-    }
+    spqr_tree.adj = triconnected_components.component_adjacency();
+    debug_assert!(spqr_tree.is_tree(), "SPQR tree should be a tree");
 
     spqr_tree
 }
@@ -45,6 +45,11 @@ pub fn get_spqr_tree(graph: &UnGraph) -> SPQRTree {
 ///
 /// After rooting the tree, `adj[u]` doesn't contain the parent component of `u` in the SPQR tree.
 pub fn get_rooted_spqr_tree(graph: &UnGraph) -> RootedSPQRTree {
+    get_rooted_spqr_tree_at(graph, 0)
+}
+
+/// Same as [`get_rooted_spqr_tree`], but roots the tree at component `root`.
+pub fn get_rooted_spqr_tree_at(graph: &UnGraph, root: usize) -> RootedSPQRTree {
     let unrooted_spqr = get_spqr_tree(graph);
     let mut rooted_spqr = RootedSPQRTree::new(&unrooted_spqr);
 
@@ -80,7 +85,7 @@ pub fn get_rooted_spqr_tree(graph: &UnGraph) -> RootedSPQRTree {
     }
 
     if rooted_spqr.blocks.comp.len() > 0 {
-        root_tree(&mut rooted_spqr, 0, &mut mark);
+        root_tree(&mut rooted_spqr, root, &mut mark);
     }
 
     rooted_spqr
@@ -93,9 +98,45 @@ mod tests {
     use petgraph::visit::{EdgeRef, IntoNodeReferences};
 
     use crate::testing::random_graphs::random_biconnected_graph;
+    use crate::triconnected_blocks::outside_structures::ComponentType;
 
     use super::*;
 
+    #[test]
+    fn test_print_ascii_envelope() {
+        let spqr_tree = get_spqr_tree(&envelope());
+        let out = spqr_tree.print_ascii();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines.len(), spqr_tree.blocks.comp.len());
+        assert!(lines[0].starts_with(&spqr_tree.blocks.comp[0].comp_type.to_string()));
+        assert!(
+            lines[1..]
+                .iter()
+                .all(|l| l.starts_with(['├', '└', '│', ' ']))
+        );
+        assert_eq!(
+            lines.iter().filter(|l| l.contains("P")).count(),
+            spqr_tree.blocks.stats().1
+        );
+    }
+
+    #[test]
+    fn test_spqr_tree_single_node() {
+        let mut in_graph = UnGraph::new_undirected();
+        in_graph.add_node(0);
+
+        let spqr_tree = get_spqr_tree(&in_graph);
+        assert!(spqr_tree.blocks.comp.is_empty());
+        assert!(spqr_tree.blocks.edges.is_empty());
+        assert!(spqr_tree.adj.is_empty());
+        assert_eq!(spqr_tree.diameter(), 0);
+        assert!(spqr_tree.center().is_empty());
+
+        let rooted = get_rooted_spqr_tree(&in_graph);
+        assert!(rooted.blocks.comp.is_empty());
+    }
+
     fn same_graphs(og_graph: &UnGraph, spqr_tree: &SPQRTree) -> bool {
         let mut edge_counts = vec![0; spqr_tree.blocks.edges.len()];
 
@@ -185,6 +226,502 @@ mod tests {
         }
     }
 
+    fn envelope() -> UnGraph {
+        crate::input::from_file("assets/envelope.in")
+    }
+
+    #[test]
+    fn test_clone_equals_original() {
+        let graph = envelope();
+        let spqr_tree = get_spqr_tree(&graph);
+        assert_eq!(spqr_tree.blocks.clone(), spqr_tree.blocks);
+        assert_eq!(spqr_tree.clone(), spqr_tree);
+        assert_eq!(
+            spqr_tree.blocks,
+            crate::triconnected::get_triconnected_components(&graph)
+        );
+
+        let rooted = get_rooted_spqr_tree(&graph);
+        assert_eq!(rooted.clone(), rooted);
+
+        // order of edges inside a component doesn't matter, anything else does
+        let mut other = spqr_tree.clone();
+        other.blocks.comp[0].edges.reverse();
+        assert_eq!(other, spqr_tree);
+        other.blocks.comp[0].comp_type = ComponentType::R;
+        assert_ne!(other, spqr_tree);
+
+        let mut rerooted = rooted.clone();
+        rerooted.reroot(spqr_tree.adj[0][0]);
+        assert_ne!(rerooted, rooted);
+    }
+
+    #[test]
+    fn test_component_vertices_envelope() {
+        let spqr_tree = get_spqr_tree(&envelope());
+        for c in 0..spqr_tree.blocks.comp.len() {
+            let size = spqr_tree.component_vertices(c).len();
+            match spqr_tree.blocks.comp[c].comp_type {
+                ComponentType::P => assert_eq!(size, 2),
+                ComponentType::S => assert!(size >= 3),
+                ComponentType::R => assert!(size >= 4),
+                ComponentType::MERGED | ComponentType::UNSURE => panic!(),
+            }
+        }
+
+        // labels are reported, not internal indices
+        let spqr_tree = get_spqr_tree(&crate::input::from_str("10,20\n20,30\n30,10\n"));
+        assert_eq!(spqr_tree.component_vertices(0), vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn test_draw_r_node() {
+        use crate::drawing_blocks::geometry::is_planar_drawing;
+        use crate::embedding::is_planar;
+
+        // the envelope has no R-node, there is nothing to draw
+        let spqr_tree = get_spqr_tree(&envelope());
+        assert!((0..spqr_tree.blocks.comp.len()).all(|c| spqr_tree.draw_r_node(c).is_none()));
+
+        // diagonals of the square make the envelope's square an R-node
+        let mut graph = envelope();
+        graph.add_edge(0.into(), 2.into(), crate::EdgeLabel::Real);
+        graph.add_edge(1.into(), 3.into(), crate::EdgeLabel::Real);
+        let spqr_tree = get_spqr_tree(&graph);
+        let r_nodes: Vec<usize> = (0..spqr_tree.blocks.comp.len())
+            .filter(|&c| spqr_tree.blocks.comp[c].comp_type == ComponentType::R)
+            .collect();
+        assert_eq!(r_nodes.len(), 1);
+
+        let skeleton = spqr_tree.skeleton_graph(r_nodes[0]);
+        assert_eq!(
+            skeleton.node_weights().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        let drawing = spqr_tree.draw_r_node(r_nodes[0]).unwrap();
+        assert_eq!(drawing.coordinates.len(), 4);
+        let (_, embedding) = is_planar(&skeleton, false);
+        assert!(is_planar_drawing(&embedding, &drawing.coordinates));
+
+        // K5 is a non-planar R-node
+        let k5 = crate::input::from_str("0,1\n0,2\n0,3\n0,4\n1,2\n1,3\n1,4\n2,3\n2,4\n3,4\n");
+        let spqr_tree = get_spqr_tree(&k5);
+        assert_eq!(spqr_tree.blocks.comp[0].comp_type, ComponentType::R);
+        assert!(spqr_tree.draw_r_node(0).is_none());
+    }
+
+    #[test]
+    fn test_is_series_parallel() {
+        let cycle = crate::input::from_str("0,1\n1,2\n2,3\n3,4\n4,0\n");
+        assert!(get_spqr_tree(&cycle).is_series_parallel());
+
+        // three internally disjoint paths between 0 and 1
+        let theta = crate::input::from_str("0,2\n2,1\n0,3\n3,1\n0,4\n4,5\n5,1\n");
+        assert!(get_spqr_tree(&theta).is_series_parallel());
+
+        // ears glued to a cycle only give S- and P-nodes
+        assert!(get_spqr_tree(&envelope()).is_series_parallel());
+
+        // diagonals of the square make it a K4 subdivision
+        let mut graph = envelope();
+        graph.add_edge(0.into(), 2.into(), crate::EdgeLabel::Real);
+        graph.add_edge(1.into(), 3.into(), crate::EdgeLabel::Real);
+        assert!(!get_spqr_tree(&graph).is_series_parallel());
+    }
+
+    #[test]
+    fn test_expansion_graph_envelope() {
+        let graph = envelope();
+        let spqr_tree = get_spqr_tree(&graph);
+        let expansion = spqr_tree.expansion_graph();
+
+        let tree_edges = spqr_tree.adj.iter().map(|a| a.len()).sum::<usize>() / 2;
+        let virtual_edges = expansion
+            .edge_weights()
+            .filter(|&&l| l == crate::EdgeLabel::Virtual)
+            .count();
+        assert_eq!(virtual_edges, tree_edges);
+        assert_eq!(expansion.edge_count(), graph.edge_count() + tree_edges);
+        assert_eq!(expansion.node_count(), graph.node_count());
+    }
+
+    #[test]
+    fn test_pertinent_graph_envelope() {
+        let graph = envelope();
+        let spqr_tree = get_spqr_tree(&graph);
+
+        // the S-node of the ear 0 - 4 - 3 hangs on the P-node of poles (0, 3)
+        let ear = (0..spqr_tree.blocks.comp.len())
+            .find(|&c| spqr_tree.component_vertices(c).contains(&4))
+            .unwrap();
+        assert_eq!(spqr_tree.adj[ear].len(), 1);
+        let bond = spqr_tree.adj[ear][0];
+        assert_eq!(spqr_tree.component_vertices(bond), vec![0, 3]);
+
+        let labels = |g: &UnGraph| g.node_weights().map(|&x| x as usize).collect::<Vec<_>>();
+
+        let pertinent = spqr_tree.pertinent_graph(bond, ear);
+        assert_eq!(labels(&pertinent), vec![0, 3, 4]);
+        assert_eq!(pertinent.edge_count(), 2);
+
+        let pertinent = spqr_tree.pertinent_graph(ear, bond);
+        assert_eq!(labels(&pertinent), vec![0, 1, 2, 3, 5, 6, 7]);
+        assert_eq!(pertinent.edge_count(), 10);
+    }
+
+    #[test]
+    fn test_pertinent_graph_labels() {
+        // three paths between 10 and 30, the one through 50 is an S-node on the P-node of poles (10, 30)
+        let graph = crate::input::from_str("10,20\n20,30\n30,40\n40,10\n10,50\n50,30\n");
+        let spqr_tree = get_spqr_tree(&graph);
+
+        let ear = (0..spqr_tree.blocks.comp.len())
+            .find(|&c| spqr_tree.component_vertices(c).contains(&50))
+            .unwrap();
+        let bond = spqr_tree.adj[ear][0];
+        assert_eq!(spqr_tree.component_vertices(bond), vec![10, 30]);
+
+        let sorted_labels = |g: &UnGraph| {
+            let mut labels: Vec<u32> = g.node_weights().copied().collect();
+            labels.sort();
+            labels
+        };
+
+        let pertinent = spqr_tree.pertinent_graph(bond, ear);
+        assert_eq!(sorted_labels(&pertinent), vec![10, 30, 50]);
+        assert_eq!(pertinent.edge_count(), 2);
+
+        let pertinent = spqr_tree.pertinent_graph(ear, bond);
+        assert_eq!(sorted_labels(&pertinent), vec![10, 20, 30, 40]);
+        assert_eq!(pertinent.edge_count(), 4);
+    }
+
+    #[test]
+    fn test_diameter_and_center_ladder() {
+        use crate::testing::grids::generate_grid_graph;
+
+        // ladder with 6 rungs, squares (S-nodes) are glued by inner rungs (P-nodes): S - P - S - ... - S
+        let graph = generate_grid_graph(2, 6);
+        let spqr_tree = get_spqr_tree(&graph);
+        assert_eq!(spqr_tree.blocks.comp.len(), 9);
+
+        assert_eq!(spqr_tree.diameter(), 8);
+
+        let center = spqr_tree.center();
+        assert_eq!(center.len(), 1);
+        assert_eq!(spqr_tree.component_vertices(center[0]), vec![2, 3, 8, 9]);
+    }
+
+    #[test]
+    fn test_leaves_path_of_cycles() {
+        use crate::testing::grids::generate_grid_graph;
+
+        // squares glued along rungs form a path S - P - S - P - S, end squares are the leaves
+        let graph = generate_grid_graph(2, 4);
+        let spqr_tree = get_spqr_tree(&graph);
+
+        let leaves = spqr_tree.leaves();
+        assert_eq!(leaves.len(), 2);
+        let mut ends: Vec<Vec<u32>> = leaves
+            .iter()
+            .map(|&c| spqr_tree.component_vertices(c))
+            .collect();
+        ends.sort();
+        assert_eq!(ends, vec![vec![0, 1, 4, 5], vec![2, 3, 6, 7]]);
+        assert!(
+            leaves
+                .iter()
+                .all(|&c| spqr_tree.blocks.comp[c].comp_type == ComponentType::S)
+        );
+
+        // a single cycle is one component without leaves
+        let cycle = get_spqr_tree(&crate::input::from_str("0,1\n1,2\n2,0\n"));
+        assert!(cycle.leaves().is_empty());
+    }
+
+    #[test]
+    fn test_reroot_matches_rooted_at() {
+        fn assert_same(a: &RootedSPQRTree, b: &RootedSPQRTree) {
+            let sorted = |adj: &Vec<Vec<usize>>| -> Vec<Vec<usize>> {
+                adj.iter()
+                    .map(|l| {
+                        let mut l = l.clone();
+                        l.sort();
+                        l
+                    })
+                    .collect()
+            };
+            assert_eq!(sorted(&a.adj), sorted(&b.adj));
+            assert_eq!(a.par_v, b.par_v);
+            assert_eq!(a.ref_edge, b.ref_edge);
+            assert_eq!(a.alloc_node, b.alloc_node);
+        }
+
+        // a chain of cycles, so rerooting walks along a long path
+        let graph = crate::testing::grids::generate_grid_graph(2, 6);
+        let mut tree = get_rooted_spqr_tree(&graph);
+        for root in 0..tree.adj.len() {
+            tree.reroot(root);
+            assert_same(&tree, &get_rooted_spqr_tree_at(&graph, root));
+        }
+
+        for i in 0..50 {
+            let graph = random_biconnected_graph(10, 16, i);
+            let mut tree = get_rooted_spqr_tree(&graph);
+            for root in (0..tree.adj.len()).rev() {
+                tree.reroot(root);
+                assert_same(&tree, &get_rooted_spqr_tree_at(&graph, root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical_hash_long_cycle() {
+        // walking around the whole cycle from every edge, in O(k^3), would take minutes here
+        let k = 3_000;
+        let cycle = |shift: usize| {
+            let input: String = (0..k)
+                .map(|i| format!("{},{}\n", (i + shift) % k, (i + shift + 1) % k))
+                .collect();
+            crate::input::from_str(&input)
+        };
+        let spqr_tree = get_spqr_tree(&cycle(0));
+        assert_eq!(spqr_tree.blocks.comp.len(), 1);
+        assert_eq!(
+            spqr_tree.canonical_hash(),
+            get_spqr_tree(&cycle(k / 3)).canonical_hash()
+        );
+    }
+
+    #[test]
+    fn test_canonical_hash() {
+        use crate::input::from_str;
+
+        // envelope with permuted labels
+        let relabeled = from_str("5,2\n2,7\n7,1\n1,5\n5,0\n1,0\n5,3\n2,3\n2,6\n7,6\n7,4\n1,4\n");
+        assert_eq!(
+            get_spqr_tree(&envelope()).canonical_hash(),
+            get_spqr_tree(&relabeled).canonical_hash()
+        );
+
+        // both are 6-cycles with a chord, but a long chord splits it into two squares
+        let long_chord = from_str("0,1\n1,2\n2,3\n3,4\n4,5\n5,0\n0,3\n");
+        let short_chord = from_str("0,1\n1,2\n2,3\n3,4\n4,5\n5,0\n0,2\n");
+        assert_ne!(
+            get_spqr_tree(&long_chord).canonical_hash(),
+            get_spqr_tree(&short_chord).canonical_hash()
+        );
+
+        // hash doesn't depend on numbering of components
+        for i in 0..20 {
+            let graph = random_biconnected_graph(10, 16, i);
+            let mut spqr_tree = get_spqr_tree(&graph);
+            let hash = spqr_tree.canonical_hash();
+
+            let k = spqr_tree.adj.len();
+            let perm: Vec<usize> = (0..k).rev().collect();
+            spqr_tree.blocks.comp.reverse();
+            spqr_tree.adj.reverse();
+            for list in spqr_tree.adj.iter_mut() {
+                for x in list.iter_mut() {
+                    *x = perm[*x];
+                }
+            }
+            assert_eq!(spqr_tree.canonical_hash(), hash);
+        }
+
+        // the same tree shape, a 5-cycle with two bonds, but bonds on adjacent or on opposite edges
+        let adjacent = from_str("0,1\n1,2\n2,3\n3,4\n4,0\n0,1\n1,2\n");
+        let opposite = from_str("0,1\n1,2\n2,3\n3,4\n4,0\n0,1\n2,3\n");
+        assert_ne!(
+            get_spqr_tree(&adjacent).canonical_hash(),
+            get_spqr_tree(&opposite).canonical_hash()
+        );
+
+        // the hasher is fixed, so the hash is the same in every run
+        assert_eq!(
+            get_spqr_tree(&envelope()).canonical_hash(),
+            6741229949626677972
+        );
+    }
+
+    #[test]
+    fn test_canonical_hash_isomorphism_classes() {
+        use crate::collections::HashMap;
+        use crate::{
+            block_cut::get_block_cut_tree, testing::graph_enumerator::GraphEnumeratorState,
+        };
+
+        // lexicographically smallest sorted edge list over all relabelings
+        fn brute_force_form(graph: &UnGraph) -> Vec<(usize, usize)> {
+            fn permutations(perm: &mut Vec<usize>, k: usize, out: &mut Vec<Vec<usize>>) {
+                if k == perm.len() {
+                    out.push(perm.clone());
+                }
+                for i in k..perm.len() {
+                    perm.swap(k, i);
+                    permutations(perm, k + 1, out);
+                    perm.swap(k, i);
+                }
+            }
+            let mut perms = Vec::new();
+            permutations(&mut (0..graph.node_count()).collect(), 0, &mut perms);
+
+            perms
+                .iter()
+                .map(|p| {
+                    let mut edges: Vec<(usize, usize)> = graph
+                        .edge_references()
+                        .map(|e| {
+                            let (a, b) = (p[e.source().index()], p[e.target().index()]);
+                            (a.min(b), a.max(b))
+                        })
+                        .collect();
+                    edges.sort();
+                    edges
+                })
+                .min()
+                .unwrap()
+        }
+
+        // equal hashes if and only if the graphs are isomorphic
+        let mut form_of_hash = HashMap::new();
+        let mut hash_of_form = HashMap::new();
+        for n in 3..=5 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: (1 << (n * (n - 1) / 2)),
+            };
+            for in_graph in enumerator {
+                let bct = get_block_cut_tree(&in_graph);
+                if bct.cut_count > 0 || bct.block_count == 0 {
+                    continue; // not biconnected
+                }
+
+                let graph = &bct.blocks[0];
+                let hash = get_spqr_tree(graph).canonical_hash();
+                let form = brute_force_form(graph);
+                assert_eq!(*form_of_hash.entry(hash).or_insert(form.clone()), form);
+                assert_eq!(*hash_of_form.entry(form).or_insert(hash), hash);
+            }
+        }
+        assert!(form_of_hash.len() > 10);
+    }
+
+    // every real edge is mapped to the component containing it
+    fn check_to_split(spqr_tree: &SPQRTree) {
+        let tricon = &spqr_tree.blocks;
+        for (c, comp) in tricon.comp.iter().enumerate() {
+            for &eid in &comp.edges {
+                if tricon.is_real[eid] {
+                    assert_eq!(tricon.to_split[eid], Some(c));
+                }
+            }
+        }
+        let owned: usize = (0..tricon.comp.len())
+            .map(|c| tricon.original_edges_of(c).len())
+            .sum();
+        assert_eq!(owned, tricon.to_split.iter().flatten().count());
+    }
+
+    #[test]
+    fn test_merge_adjacent() {
+        let graph = envelope();
+        let mut spqr_tree = get_spqr_tree(&graph);
+        let n_comps = spqr_tree.adj.len();
+
+        let s_node = (0..n_comps)
+            .find(|&c| spqr_tree.blocks.comp[c].comp_type == ComponentType::S)
+            .unwrap();
+        let neighbor = spqr_tree.adj[s_node][0];
+        let expected_len = spqr_tree.blocks.comp[s_node].edges.len()
+            + spqr_tree.blocks.comp[neighbor].edges.len()
+            - 2;
+
+        spqr_tree.merge_adjacent(s_node, neighbor);
+        assert_eq!(spqr_tree.adj.len(), n_comps - 1);
+        let merged = &spqr_tree.blocks.comp[s_node.min(neighbor)];
+        assert_eq!(merged.edges.len(), expected_len);
+        // in a canonical tree neighbors of an S-node are not S-nodes
+        assert_eq!(merged.comp_type, ComponentType::MERGED);
+        assert!(same_graphs(&graph, &spqr_tree));
+        check_to_split(&spqr_tree);
+
+        // coarsen random trees down to a single skeleton
+        for i in 0..50 {
+            let graph = random_biconnected_graph(8 + i / 5, 12 + i, i);
+            let mut spqr_tree = get_spqr_tree(&graph);
+            while spqr_tree.adj.len() > 1 {
+                let a = spqr_tree.adj.len() - 1;
+                let b = spqr_tree.adj[a][0];
+                spqr_tree.merge_adjacent(a, b);
+                assert!(same_graphs(&graph, &spqr_tree));
+                check_to_split(&spqr_tree);
+                let tree_edges: usize = spqr_tree.adj.iter().map(|l| l.len()).sum();
+                assert_eq!(tree_edges, 2 * (spqr_tree.adj.len() - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_diameter_and_center_small() {
+        let graph = envelope();
+        let spqr_tree = get_spqr_tree(&graph);
+        assert_eq!(spqr_tree.diameter(), 4);
+        assert_eq!(spqr_tree.center().len(), 1);
+
+        // a bond, only one component
+        let mut graph = UnGraph::new_undirected();
+        graph.add_node(0);
+        graph.add_node(1);
+        for _ in 0..3 {
+            graph.add_edge(0.into(), 1.into(), crate::EdgeLabel::Real);
+        }
+        let spqr_tree = get_spqr_tree(&graph);
+        assert_eq!(spqr_tree.diameter(), 0);
+        assert_eq!(spqr_tree.center(), vec![0]);
+    }
+
+    #[test]
+    fn test_spqr_tree_is_tree_enumerated() {
+        use crate::{
+            block_cut::get_block_cut_tree, testing::graph_enumerator::GraphEnumeratorState,
+        };
+
+        for n in 2..=6 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: (1 << (n * (n - 1) / 2)),
+            };
+
+            for in_graph in enumerator {
+                let bct = get_block_cut_tree(&in_graph);
+                if bct.cut_count > 0 || bct.block_count == 0 {
+                    continue; // not biconnected
+                }
+
+                let spqr_tree = get_spqr_tree(&bct.blocks[0]);
+                assert!(spqr_tree.is_tree());
+                let edges: usize = spqr_tree.adj.iter().map(|l| l.len()).sum::<usize>() / 2;
+                assert_eq!(edges, spqr_tree.adj.len().saturating_sub(1));
+            }
+        }
+
+        // a cycle among components is detected
+        let mut spqr_tree = get_spqr_tree(&envelope());
+        let k = spqr_tree.adj.len();
+        assert!(k >= 3);
+        let (a, b) = (0..k)
+            .flat_map(|a| (0..k).map(move |b| (a, b)))
+            .find(|&(a, b)| a < b && !spqr_tree.adj[a].contains(&b))
+            .unwrap();
+        spqr_tree.adj[a].push(b);
+        spqr_tree.adj[b].push(a);
+        assert!(!spqr_tree.is_tree());
+    }
+
     #[cfg(all(test, not(debug_assertions)))]
     #[test]
     fn test_spqr_tree_exhaustive() {
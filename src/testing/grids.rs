@@ -1,8 +1,11 @@
 use crate::{EdgeLabel, UnGraph};
 use petgraph::graph::NodeIndex;
 
+pub use crate::drawing_blocks::geometry::Point;
+
 /// Generates a grid graph with the specified number of rows and columns.
-#[allow(dead_code)]
+///
+/// Vertex in row `r` and column `c` has index (and label) `r * cols + c`.
 pub fn generate_grid_graph(rows: usize, cols: usize) -> UnGraph {
     assert!(rows > 1 && cols > 1); // we want biconnected graph
     let mut graph = UnGraph::new_undirected();
@@ -16,10 +19,18 @@ pub fn generate_grid_graph(rows: usize, cols: usize) -> UnGraph {
     for r in 0..rows {
         for c in 0..cols {
             if r + 1 < rows {
-                graph.add_edge(NodeIndex::new(r * cols + c), NodeIndex::new((r + 1) * cols + c), EdgeLabel::Real);
+                graph.add_edge(
+                    NodeIndex::new(r * cols + c),
+                    NodeIndex::new((r + 1) * cols + c),
+                    EdgeLabel::Real,
+                );
             }
             if c + 1 < cols {
-                graph.add_edge(NodeIndex::new(r * cols + c), NodeIndex::new(r * cols + c + 1), EdgeLabel::Real);
+                graph.add_edge(
+                    NodeIndex::new(r * cols + c),
+                    NodeIndex::new(r * cols + c + 1),
+                    EdgeLabel::Real,
+                );
             }
         }
     }
@@ -27,41 +38,14 @@ pub fn generate_grid_graph(rows: usize, cols: usize) -> UnGraph {
     graph
 }
 
-#[derive(Clone, Copy, Debug)]
-#[derive(PartialEq)]
-pub struct Point {
-    x: i64,
-    y: i64,
-}
-
-impl Point {
-    pub fn new(x: i64, y: i64) -> Self {
-        Point { x, y }
-    }
-
-    pub fn sub(&self, p: &Point) -> Point {
-        Point::new(self.x - p.x, self.y - p.y)
-    }
-
-    pub fn cross(&self, p: &Point) -> i64 {
-        self.x * p.y - self.y * p.x
-    }
-
-    pub fn cross2(&self, p: &Point, q: &Point) -> i64 {
-        p.sub(self).cross(&q.sub(self))
-    }
-
-    pub fn half(&self) -> bool {
-        self.y < 0 || (self.y == 0 && self.x < 0)
-    }
-}
-
-#[allow(dead_code)]
+/// Returns a planar straight-line embedding of the grid generated by [`generate_grid_graph`].
+///
+/// Vertex in row `r` and column `c` is placed at `(c, -r)`, so it works for any `rows x cols`.
 pub fn get_arbitrary_embedding_of_grid(rows: usize, cols: usize) -> Vec<Point> {
-    let mut points = vec![Point { x: 0, y: 0 }; rows * cols];
+    let mut points = vec![Point::new(0, 0); rows * cols];
     for r in 0..rows {
         for c in 0..cols {
-            points[r * cols + c] = Point { x: c as i64, y: -(r as i64) };
+            points[r * cols + c] = Point::new(c as i64, -(r as i64));
         }
     }
 
@@ -74,8 +58,31 @@ mod tests {
 
     #[test]
     fn test_square_embedding() {
-        let emb = get_arbitrary_embedding_of_grid(2,2);
-        assert_eq!(emb, vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }, Point { x: 0, y: -1 }, Point { x: 1, y: -1 }]);
+        let emb = get_arbitrary_embedding_of_grid(2, 2);
+        assert_eq!(
+            emb,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(0, -1),
+                Point::new(1, -1)
+            ]
+        );
+    }
 
+    #[test]
+    fn test_rectangular_embedding_faces() {
+        use crate::drawing_blocks::dual_graph::get_dual_graph;
+
+        for rows in 2..8 {
+            for cols in 2..8 {
+                let graph = generate_grid_graph(rows, cols);
+                let points = get_arbitrary_embedding_of_grid(rows, cols);
+                let dual_graph = get_dual_graph(&points, &graph);
+
+                // inner squares and the outer face
+                assert_eq!(dual_graph.faces.len(), (rows - 1) * (cols - 1) + 1);
+            }
+        }
     }
 }
@@ -1,3 +1,34 @@
 pub(crate) mod graph_enumerator;
-pub(crate) mod grids;
-pub(crate) mod random_graphs;
+pub mod grids;
+pub mod random_graphs;
+
+use crate::UnGraph;
+
+/// Checks whether two graphs are isomorphic by an isomorphism preserving edge labels.
+///
+/// Node labels are ignored, as they usually are just identifiers.
+pub fn iso_with_labels(g1: &UnGraph, g2: &UnGraph) -> bool {
+    petgraph::algo::is_isomorphic_matching(g1, g2, |_, _| true, |a, b| a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EdgeLabel;
+
+    #[test]
+    fn test_iso_with_labels() {
+        let g1 = crate::input::from_str("0,1\n1,2\n2,0,s\n");
+        let g2 = crate::input::from_str("5,6,s\n6,7\n7,5\n");
+        assert!(iso_with_labels(&g1, &g2));
+
+        let mut g3 = g1.clone();
+        let e = g3
+            .edge_indices()
+            .find(|&e| g3[e] == EdgeLabel::Structure)
+            .unwrap();
+        g3[e] = EdgeLabel::Real;
+        assert!(petgraph::algo::is_isomorphic(&g1, &g3));
+        assert!(!iso_with_labels(&g1, &g3));
+    }
+}
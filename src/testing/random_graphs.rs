@@ -64,3 +64,71 @@ pub fn random_biconnected_graph(n: usize, m: usize, seed: usize) -> UnGraph {
 
     bct.blocks[0].clone()
 }
+
+/// Generates a random biconnected series-parallel graph on `n` vertices (its SPQR tree has no R-nodes).
+///
+/// Starts with a triangle (or a single edge for `n < 3`) and repeatedly picks a random edge `(u, v)` and either:
+/// - subdivides it (series composition), or
+/// - adds a path `u - w - v` next to it (parallel composition).
+///
+/// Both operations add one vertex and keep the graph simple.
+pub fn random_series_parallel(n: usize, seed: usize) -> UnGraph {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let mut graph = UnGraph::new_undirected();
+
+    for i in 0..n.min(3) {
+        graph.add_node(i.try_into().unwrap());
+    }
+    if n == 2 {
+        graph.add_edge(graph.from_index(0), graph.from_index(1), EdgeLabel::Real);
+    }
+    if n < 3 {
+        return graph;
+    }
+    graph.add_edge(graph.from_index(0), graph.from_index(1), EdgeLabel::Real);
+    graph.add_edge(graph.from_index(1), graph.from_index(2), EdgeLabel::Real);
+    graph.add_edge(graph.from_index(2), graph.from_index(0), EdgeLabel::Real);
+
+    for i in 3..n {
+        let w = graph.add_node(i.try_into().unwrap());
+        let e = petgraph::graph::EdgeIndex::new(rng.random_range(0..graph.edge_count()));
+        let (u, v) = graph.edge_endpoints(e).unwrap();
+        if rng.random_bool(0.5) {
+            // series: u - w - v replaces u - v
+            graph.remove_edge(e);
+        }
+        graph.add_edge(u, w, EdgeLabel::Real);
+        graph.add_edge(w, v, EdgeLabel::Real);
+    }
+
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spqr_tree::get_spqr_tree;
+    use crate::triconnected_blocks::outside_structures::ComponentType;
+
+    #[test]
+    fn test_random_series_parallel_has_no_r_nodes() {
+        for n in 3..30 {
+            for seed in 0..10 {
+                let graph = random_series_parallel(n, seed);
+                assert_eq!(graph.node_count(), n);
+
+                let bct = get_block_cut_tree(&graph);
+                assert_eq!(bct.block_count, 1);
+
+                let spqr_tree = get_spqr_tree(&graph);
+                assert!(
+                    spqr_tree
+                        .blocks
+                        .comp
+                        .iter()
+                        .all(|c| c.comp_type != ComponentType::R)
+                );
+            }
+        }
+    }
+}
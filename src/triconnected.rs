@@ -6,10 +6,10 @@ use crate::{
     block_cut::get_block_cut_tree,
     triconnected_blocks::{
         acceptable_adj::make_adjacency_lists_acceptable,
-        graph_internal::GraphInternal,
+        graph_internal::{EdgeType, GraphInternal},
         handle_duplicate_edges::handle_duplicate_edges,
         merge_components::merge_components,
-        outside_structures::{Component, ComponentType, EdgeType, TriconnectedComponents},
+        outside_structures::{Component, ComponentType, TriconnectedComponents},
         palm_dfs::run_palm_dfs,
         pathfinder::run_pathfinder,
     },
@@ -358,6 +358,9 @@ fn find_components(
 ///
 /// After merging all P nodes with P nodes and S nodes with S nodes, the final set of triconnected components is obtained.
 ///
+/// A graph on two vertices is a single P node (even with only two edges), unless it is a single edge,
+/// in which case there are no components at all.
+///
 /// ## Example (visualized using .dot file generated with visualize.rs from triconnected_blocks)
 ///
 /// ![TRICON_Full][tricon_full]
@@ -367,74 +370,181 @@ fn find_components(
 /// - [Explaining Hopcroft, Tarjan, Gutwenger, and Mutzel’s SPQR Decomposition Algorithm] (https://shoyamanishi.github.io/wailea/docs/spqr_explained/HTGMExplained.pdf)
 #[embed_doc_image("tricon_full", "assets/split_components.svg")]
 pub fn get_triconnected_components(in_graph: &UnGraph) -> TriconnectedComponents {
+    get_triconnected_components_with_trace(in_graph).0
+}
+
+/// Returns numbers of S-nodes, P-nodes and R-nodes of a biconnected graph, like
+/// [`TriconnectedComponents::stats`] of [`get_triconnected_components`].
+///
+/// It runs the same search, but skips mapping edges of the components back to the input graph.
+pub fn triconnected_type_counts(in_graph: &UnGraph) -> (usize, usize, usize) {
     let n = in_graph.node_count();
     let m = in_graph.edge_count();
-    let root = 0;
-
-    let mut split_components = Vec::new();
 
-    assert!(get_block_cut_tree(&in_graph).block_count == 1);
+    assert!(get_block_cut_tree(in_graph).block_count == 1);
     assert!(n >= 2);
 
     if n == 2 {
-        let mut c = Component::new(ComponentType::P);
-        let mut edges = Vec::new();
-        for i in in_graph.edge_references() {
-            let (s, t) = (i.source().index(), i.target().index());
-            edges.push((s, t));
-            c.push_edge(i.id().index(), &mut GraphInternal::new(0, 0), true);
-        }
+        // a single bond, unless there is just one edge
+        return (0, usize::from(m >= 2), 0);
+    }
 
-        if m >= 3 {
-            return TriconnectedComponents {
-                comp: vec![c],
-                edges,
-                is_real: vec![true; m],
-                to_split: vec![Some(0); m],
-            };
-        } else {
-            return TriconnectedComponents {
-                comp: vec![],
-                edges,
-                is_real: vec![true; m],
-                to_split: vec![Some(0); m],
-            };
+    let (split_components, _) = find_merged_components(in_graph);
+    let count = |t: ComponentType| split_components.iter().filter(|c| c.comp_type == t).count();
+    (
+        count(ComponentType::S),
+        count(ComponentType::P),
+        count(ComponentType::R),
+    )
+}
+
+/// Palm tree built by the decomposition, returned by [`get_triconnected_components_with_trace`].
+///
+/// Vertices are internal indices of the input graph, arrays are in their final state,
+/// so `num` is the numbering after the pathfinder and `low1`, `low2` refer to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalmTreeTrace {
+    /// Number of every vertex in the palm tree, the root gets `0`.
+    pub num: Vec<usize>,
+    /// Lowest `num` reachable from the subtree of a vertex with at most one back edge.
+    pub low1: Vec<usize>,
+    /// Second lowest such `num`, `low1 <= low2`.
+    pub low2: Vec<usize>,
+    /// Number of vertices in the subtree of a vertex.
+    pub sub: Vec<usize>,
+    /// Parent of a vertex in the palm tree, `None` for the root.
+    pub par: Vec<Option<usize>>,
+    /// Sources of back edges entering a vertex, in the order used by the pathfinder.
+    /// Empty for a graph with two vertices, as the pathfinder is not run.
+    pub high: Vec<Vec<usize>>,
+}
+
+impl PalmTreeTrace {
+    fn new(graph: &GraphInternal) -> Self {
+        let n = graph.n;
+        PalmTreeTrace {
+            num: graph.num[..n].to_vec(),
+            low1: graph.low1[..n].to_vec(),
+            low2: graph.low2[..n].to_vec(),
+            sub: graph.sub[..n].to_vec(),
+            par: graph.par[..n].to_vec(),
+            high: (0..n)
+                .map(|v| {
+                    graph.high[v]
+                        .iter()
+                        .map(|&eid| graph.get_other_vertex(eid, v))
+                        .collect()
+                })
+                .collect(),
         }
     }
+}
 
-    let mut graph = GraphInternal::from_petgraph(in_graph);
+/// What the pathfinder computed, returned by [`run_pathfinder_traced`].
+///
+/// Vertices are internal indices of the input graph. Edges are the ones the pathfinder walks,
+/// so each bundle of parallel edges appears once.
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathfinderTrace {
+    /// `newnum(v)` of every vertex, the root gets `0`.
+    pub newnum: Vec<usize>,
+    /// Sources of back edges entering a vertex (`high(v)`), in the order used by the algorithm.
+    pub high: Vec<Vec<usize>>,
+    /// Edges of the palm tree, tree edges go from parent to child and back edges from descendant to ancestor.
+    ///
+    /// Vertices come by `newnum`, edges of a vertex in the order of its (acceptable) adjacency list,
+    /// so consecutive edges with the same source are the order in which the pathfinder takes them.
+    pub edges: Vec<(usize, usize)>,
+    /// `starts_path(e)` of every edge of `edges`, set on the first edge of each path.
+    pub starts_path: Vec<bool>,
+}
 
-    handle_duplicate_edges(&mut graph, &mut split_components);
+/// Runs the steps of the decomposition up to the pathfinder and returns `newnum(v)`, `high(v)` and `starts_path(e)`.
+///
+/// Meant for debugging, results can be compared with a hand computation on small graphs,
+/// so it is compiled only with debug assertions. The graph is prepared as in [`get_triconnected_components`],
+/// with DFS started at the vertex with internal index `0`.
+///
+/// Input should be biconnected.
+#[cfg(debug_assertions)]
+pub fn run_pathfinder_traced(in_graph: &UnGraph) -> PathfinderTrace {
+    assert!(get_block_cut_tree(in_graph).block_count == 1);
+    assert!(in_graph.node_count() >= 2);
+
+    let graph = build_palm_tree(in_graph, 0, &mut Vec::new());
+    let n = graph.n;
+
+    let mut edges = Vec::new();
+    let mut starts_path = Vec::new();
+    for &u in &graph.numrev[..n] {
+        for &eid in &graph.adj[u] {
+            edges.push(graph.edges[eid]);
+            starts_path.push(graph.starts_path[eid]);
+        }
+    }
 
-    // first dfs, computes num, low1, low2, sub, par, deg, edge_type and fixes the edges' direction
-    run_palm_dfs(&mut graph, root);
+    PathfinderTrace {
+        newnum: graph.num[..n].to_vec(),
+        high: (0..n)
+            .map(|v| {
+                graph.high[v]
+                    .iter()
+                    .map(|&eid| graph.get_other_vertex(eid, v))
+                    .collect()
+            })
+            .collect(),
+        edges,
+        starts_path,
+    }
+}
 
-    // compute acceptable adjacency list structure
-    make_adjacency_lists_acceptable(&mut graph);
+/// Works like [`get_triconnected_components`], but also returns the palm tree used by the algorithm,
+/// so it can be inspected or drawn, see [`PalmTreeTrace`].
+pub fn get_triconnected_components_with_trace(
+    in_graph: &UnGraph,
+) -> (TriconnectedComponents, PalmTreeTrace) {
+    let n = in_graph.node_count();
+    let m = in_graph.edge_count();
+    let root = 0;
 
-    // pathfinder part: calculate high(v), newnum(v), starts_path(e) and newnum(v)
-    run_pathfinder(root, &mut graph);
+    assert!(get_block_cut_tree(&in_graph).block_count == 1);
+    assert!(n >= 2);
 
-    // find split_components
-    let mut estack = Vec::new();
-    let mut tstack = Vec::new();
-    find_components(
-        root,
-        root,
-        graph.m,
-        &mut graph,
-        &mut estack,
-        &mut tstack,
-        &mut split_components,
-    );
+    if n == 2 {
+        // every edge connects the same two vertices, so the whole graph is a single bond
+        let edges = in_graph
+            .edge_references()
+            .map(|e| {
+                let (s, t) = (e.source().index(), e.target().index());
+                if s < t { (s, t) } else { (t, s) }
+            })
+            .collect::<Vec<_>>();
+
+        // a single edge is not split any further
+        let (comp, to_split) = if m >= 2 {
+            let mut c = Component::new(ComponentType::P);
+            c.edges = (0..m).collect();
+            (vec![c], vec![Some(0); m])
+        } else {
+            (vec![], vec![None; m])
+        };
 
-    let mut component = Component::new(ComponentType::UNSURE);
-    while let Some(eid) = estack.pop() {
-        component.push_edge(eid, &mut graph, false);
+        let mut graph = GraphInternal::from_petgraph(in_graph);
+        run_palm_dfs(&mut graph, root);
+
+        return (
+            TriconnectedComponents {
+                comp,
+                edges,
+                is_real: vec![true; m],
+                to_split,
+            },
+            PalmTreeTrace::new(&graph),
+        );
     }
-    component.commit(&mut split_components);
 
-    merge_components(graph.m, &mut split_components);
+    let (mut split_components, graph) = find_merged_components(in_graph);
 
     let mut is_real_edge = vec![false; graph.m];
     let mut real_to_split_component = vec![None; graph.m];
@@ -467,6 +577,11 @@ pub fn get_triconnected_components(in_graph: &UnGraph) -> TriconnectedComponents
         }
     }
 
+    // real edges keep the order of the input graph, parallel copies are taken in the same order as well
+    for indices in pair_to_indices.values_mut() {
+        indices.reverse();
+    }
+
     let mut new_edges = Vec::with_capacity(graph.m);
     let mut old_eid_to_new = vec![0; graph.m];
     for eid in in_graph.edge_references() {
@@ -508,19 +623,80 @@ pub fn get_triconnected_components(in_graph: &UnGraph) -> TriconnectedComponents
         }
     }
 
-    TriconnectedComponents {
-        comp: split_components,
-        edges: new_edges,
-        is_real: new_is_real_edge,
-        to_split: new_real_to_split_component,
+    (
+        TriconnectedComponents {
+            comp: split_components,
+            edges: new_edges,
+            is_real: new_is_real_edge,
+            to_split: new_real_to_split_component,
+        },
+        PalmTreeTrace::new(&graph),
+    )
+}
+
+/// Builds the internal graph on which split components are searched: bundles of parallel edges are
+/// replaced by single edges (their bonds are pushed to `split_components`), then the palm tree is built
+/// from `root` and the pathfinder is run.
+fn build_palm_tree(
+    in_graph: &UnGraph,
+    root: usize,
+    split_components: &mut Vec<Component>,
+) -> GraphInternal {
+    let mut graph = GraphInternal::from_petgraph(in_graph);
+
+    handle_duplicate_edges(&mut graph, split_components);
+
+    // first dfs, computes num, low1, low2, sub, par, deg, edge_type and fixes the edges' direction
+    run_palm_dfs(&mut graph, root);
+
+    // compute acceptable adjacency list structure
+    make_adjacency_lists_acceptable(&mut graph);
+
+    // pathfinder part: calculate high(v), newnum(v), starts_path(e) and newnum(v)
+    run_pathfinder(root, &mut graph);
+
+    graph
+}
+
+/// Runs the split components search on a biconnected graph with at least 3 vertices and merges the components.
+///
+/// Edges of the components are numbered as in the returned internal graph, not as in `in_graph`.
+fn find_merged_components(in_graph: &UnGraph) -> (Vec<Component>, GraphInternal) {
+    let root = 0;
+    let mut split_components = Vec::new();
+    let mut graph = build_palm_tree(in_graph, root, &mut split_components);
+
+    // find split_components
+    let mut estack = Vec::new();
+    let mut tstack = Vec::new();
+    find_components(
+        root,
+        root,
+        graph.m,
+        &mut graph,
+        &mut estack,
+        &mut tstack,
+        &mut split_components,
+    );
+
+    let mut component = Component::new(ComponentType::UNSURE);
+    while let Some(eid) = estack.pop() {
+        component.push_edge(eid, &mut graph, false);
     }
+    component.commit(&mut split_components);
+
+    merge_components(graph.m, &mut split_components);
+
+    (split_components, graph)
 }
 
 #[cfg(test)]
 mod tests {
     use petgraph::visit::{IntoNodeReferences, NodeIndexable};
 
-    use crate::testing::random_graphs::random_biconnected_graph;
+    use crate::{
+        EdgeLabel, testing::random_graphs::random_biconnected_graph, types::edge_multiplicity,
+    };
 
     use super::*;
 
@@ -640,15 +816,57 @@ mod tests {
             break;
         }
 
-        let mut direct_cnt = 0;
-        for v in in_graph.neighbors(in_graph.from_index(s)) {
-            if v.index() == t {
-                direct_cnt += 1;
+        let direct_cnt = edge_multiplicity(
+            in_graph,
+            in_graph[in_graph.from_index(s)],
+            in_graph[in_graph.from_index(t)],
+        );
+
+        vis.iter().any(|&v| !v) || direct_cnt > 1
+    }
+    /// Checks that every R-node is minimal, i.e. its skeleton (with virtual edges) has no separation pair.
+    fn no_r_node_has_separation_pair(tricon: &TriconnectedComponents, in_graph: &UnGraph) -> bool {
+        let mut to_skeleton = vec![usize::MAX; in_graph.node_count()];
+
+        for c in tricon.comp.iter() {
+            if c.comp_type != ComponentType::R {
+                continue;
+            }
+
+            let mut skeleton = UnGraph::new_undirected();
+            let mut vertices = vec![];
+            for &eid in &c.edges {
+                let (s, t) = tricon.edges[eid];
+                for v in [s, t] {
+                    if to_skeleton[v] == usize::MAX {
+                        to_skeleton[v] = skeleton.add_node(v as u32).index();
+                        vertices.push(v);
+                    }
+                }
+                skeleton.add_edge(
+                    skeleton.from_index(to_skeleton[s]),
+                    skeleton.from_index(to_skeleton[t]),
+                    EdgeLabel::Real,
+                );
+            }
+
+            for v in vertices {
+                to_skeleton[v] = usize::MAX;
+            }
+
+            let k = skeleton.node_count();
+            for x in 0..k {
+                for y in x + 1..k {
+                    if is_splitpair(&skeleton, x, y) {
+                        return false;
+                    }
+                }
             }
         }
 
-        vis.iter().any(|&v| !v) || direct_cnt > 1
+        true
     }
+
     fn verify_components(
         in_graph: &UnGraph,
         split_components: &Vec<Component>,
@@ -767,6 +985,296 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_r_nodes_are_minimal() {
+        use crate::testing::graph_enumerator::GraphEnumeratorState;
+
+        for n in 4..=6 {
+            let enumerator = GraphEnumeratorState {
+                n,
+                mask: 0,
+                last_mask: (1 << (n * (n - 1) / 2)),
+            };
+
+            for in_graph in enumerator {
+                let bct = get_block_cut_tree(&in_graph);
+                if bct.cut_count > 0 || bct.block_count == 0 {
+                    continue; // not biconnected
+                }
+
+                let in_graph = bct.blocks[0].clone();
+                if in_graph.node_count() < 2 {
+                    continue;
+                }
+
+                let tricon = get_triconnected_components(&in_graph);
+                assert!(no_r_node_has_separation_pair(&tricon, &in_graph));
+            }
+        }
+
+        // multigraphs
+        for i in 0..100 {
+            let in_graph = random_biconnected_graph(2 + i / 10, 1 + i, i);
+            let tricon = get_triconnected_components(&in_graph);
+            assert!(no_r_node_has_separation_pair(&tricon, &in_graph));
+        }
+    }
+
+    #[test]
+    fn test_triconnected_trace() {
+        for i in 0..100 {
+            let in_graph = random_biconnected_graph(2 + i / 10, 1 + i, i);
+            let n = in_graph.node_count();
+
+            let (tricon, trace) = get_triconnected_components_with_trace(&in_graph);
+            verify_components(&in_graph, &tricon.comp, &tricon.edges);
+
+            assert_eq!(trace.num.len(), n);
+            assert!((0..n).all(|u| trace.low1[u] <= trace.low2[u]));
+            assert!((0..n).all(|u| trace.low1[u] <= trace.num[u]));
+            assert_eq!(trace.sub[0], n);
+            assert!(trace.par[0].is_none());
+            assert!((1..n).all(|u| trace.par[u].is_some()));
+            // back edges go from descendants to ancestors
+            assert!((0..n).all(|v| trace.high[v].iter().all(|&u| trace.num[u] > trace.num[v])));
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_pathfinder_starts_path() {
+        // wheel on 5 vertices: cycle 1 - 2 - 3 - 4 with hub 0
+        let in_graph = crate::input::from_str("0,1\n0,2\n0,3\n0,4\n1,2\n2,3\n3,4\n4,1\n");
+        let n = in_graph.node_count();
+        let trace = run_pathfinder_traced(&in_graph);
+
+        // newnum is a permutation with the root first
+        let mut sorted = trace.newnum.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+        assert_eq!(trace.newnum[0], 0);
+
+        // every path ends with a back edge, so there are as many paths as back edges
+        let back_edges = in_graph.edge_count() - (n - 1);
+        assert_eq!(trace.edges.len(), in_graph.edge_count());
+        assert_eq!(trace.starts_path.iter().filter(|&&s| s).count(), back_edges);
+        assert_eq!(
+            trace.high.iter().map(|h| h.len()).sum::<usize>(),
+            back_edges
+        );
+
+        // a path continues with the first edge out of a vertex, other edges start new paths
+        for (i, &(u, _)) in trace.edges.iter().enumerate() {
+            let first = i == 0 || trace.edges[i - 1].0 != u;
+            assert_eq!(trace.starts_path[i], u == 0 || !first);
+        }
+    }
+
+    #[test]
+    fn test_triconnected_two_vertices() {
+        for m in 1..=4 {
+            let mut in_graph = UnGraph::new_undirected();
+            let a = in_graph.add_node(0);
+            let b = in_graph.add_node(1);
+            for i in 0..m {
+                // mix directions, edges should be normalized anyway
+                if i % 2 == 0 {
+                    in_graph.add_edge(a, b, EdgeLabel::Real);
+                } else {
+                    in_graph.add_edge(b, a, EdgeLabel::Real);
+                }
+            }
+
+            let tricon = get_triconnected_components(&in_graph);
+            verify_components(&in_graph, &tricon.comp, &tricon.edges);
+
+            assert_eq!(tricon.edges, vec![(0, 1); m]);
+            assert_eq!(tricon.is_real, vec![true; m]);
+
+            if m == 1 {
+                assert!(tricon.comp.is_empty());
+                assert_eq!(tricon.to_split, vec![None]);
+            } else {
+                assert_eq!(tricon.comp.len(), 1);
+                assert_eq!(tricon.comp[0].comp_type, ComponentType::P);
+                assert_eq!(tricon.comp[0].edges, (0..m).collect::<Vec<_>>());
+                assert_eq!(tricon.to_split, vec![Some(0); m]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_original_edges_envelope() {
+        let in_graph = crate::input::from_file("assets/envelope.in");
+        let tricon = get_triconnected_components(&in_graph);
+
+        // every edge of the envelope is owned by exactly one component
+        let mut owned: Vec<usize> = (0..tricon.comp.len())
+            .flat_map(|c| tricon.original_edges_of(c))
+            .collect();
+        owned.sort();
+        assert_eq!(owned, (0..in_graph.edge_count()).collect::<Vec<_>>());
+        for c in 0..tricon.comp.len() {
+            assert!(
+                tricon
+                    .original_edges_of(c)
+                    .iter()
+                    .all(|&e| tricon.to_split[e] == Some(c))
+            );
+        }
+    }
+
+    #[test]
+    fn test_edges_follow_input_order() {
+        let in_graph = crate::input::from_str("5,7\n7,9\n9,5\n5,11\n11,7\n9,11\n7,5\n");
+        let tricon = get_triconnected_components(&in_graph);
+
+        let (s, t) = tricon.edges[0];
+        let mut first = [
+            in_graph[in_graph.from_index(s)],
+            in_graph[in_graph.from_index(t)],
+        ];
+        first.sort();
+        assert_eq!(first, [5, 7]);
+        assert!(tricon.is_real[0]);
+
+        for i in 0..200 {
+            let n = 2 + i / 10;
+            let in_graph = random_biconnected_graph(n, 1 + i, i);
+            let tricon = get_triconnected_components(&in_graph);
+            for e in in_graph.edge_references() {
+                let (s, t) = (e.source().index(), e.target().index());
+                assert_eq!(tricon.edges[e.id().index()], (s.min(t), s.max(t)));
+                assert!(tricon.is_real[e.id().index()]);
+            }
+            assert!(tricon.is_real[in_graph.edge_count()..].iter().all(|&r| !r));
+        }
+    }
+
+    #[cfg(all(test, not(debug_assertions)))]
+    #[test]
+    fn test_triconnected_giant_bond() {
+        let k = 100_000;
+        let mut in_graph = UnGraph::new_undirected();
+        let a = in_graph.add_node(0);
+        let b = in_graph.add_node(1);
+        for _ in 0..k {
+            in_graph.add_edge(a, b, EdgeLabel::Real);
+        }
+
+        let tricon = get_triconnected_components(&in_graph);
+        assert_eq!(tricon.comp.len(), 1);
+        assert_eq!(tricon.comp[0].comp_type, ComponentType::P);
+        assert_eq!(tricon.comp[0].edges.len(), k);
+
+        // a third vertex makes the bond go through duplicate edge handling and merging
+        let c = in_graph.add_node(2);
+        in_graph.add_edge(b, c, EdgeLabel::Real);
+        in_graph.add_edge(c, a, EdgeLabel::Real);
+        let tricon = get_triconnected_components(&in_graph);
+        assert_eq!(tricon.stats().0, 1);
+        let bonds: Vec<_> = tricon
+            .comp
+            .iter()
+            .filter(|c| c.comp_type == ComponentType::P)
+            .collect();
+        assert_eq!(bonds.len(), 1);
+        // all parallel edges and the virtual edge to the triangle
+        assert_eq!(bonds[0].edges.len(), k + 1);
+    }
+
+    #[test]
+    fn test_triconnected_type_counts() {
+        for i in 0..200 {
+            let n = 2 + i / 10;
+            let in_graph = random_biconnected_graph(n, 1 + i, i);
+            let (s, p, r, _) = get_triconnected_components(&in_graph).stats();
+            assert_eq!(triconnected_type_counts(&in_graph), (s, p, r));
+        }
+    }
+
+    #[test]
+    fn test_component_adjacency_envelope() {
+        let envelope = crate::input::from_file("assets/envelope.in");
+        let tricon = get_triconnected_components(&envelope);
+        let adj = tricon.component_adjacency();
+
+        // it's a tree
+        let tree_edges: usize = adj.iter().map(|l| l.len()).sum();
+        assert_eq!(tree_edges, 2 * (tricon.comp.len() - 1));
+
+        // adjacent components share exactly one virtual edge, others share none
+        for (i, a) in tricon.comp.iter().enumerate() {
+            for (j, b) in tricon.comp.iter().enumerate() {
+                let shared = a
+                    .edges
+                    .iter()
+                    .filter(|eid| !tricon.is_real[**eid] && b.edges.contains(eid))
+                    .count();
+                if i != j {
+                    assert_eq!(shared, usize::from(adj[i].contains(&j)));
+                }
+            }
+        }
+
+        // the square is an S-node, every side is a bond with the square and with the cycle of its ear
+        let shape = |c: usize| (tricon.comp[c].comp_type, adj[c].len());
+        let center = (0..tricon.comp.len()).find(|&c| adj[c].len() == 4).unwrap();
+        assert_eq!(shape(center), (ComponentType::S, 4));
+        for &p in &adj[center] {
+            assert_eq!(shape(p), (ComponentType::P, 2));
+            let &ear = adj[p].iter().find(|&&c| c != center).unwrap();
+            assert_eq!(shape(ear), (ComponentType::S, 1));
+            assert_eq!(tricon.comp[ear].edges.len(), 3);
+        }
+        assert_eq!(tricon.comp.len(), 9);
+    }
+
+    #[test]
+    fn test_separation_vertices() {
+        let brute = |in_graph: &UnGraph| {
+            let n = in_graph.node_count();
+            let mut vertices: Vec<u32> = (0..n)
+                .flat_map(|s| (s + 1..n).map(move |t| (s, t)))
+                .filter(|&(s, t)| is_splitpair(in_graph, s, t))
+                .flat_map(|(s, t)| [s as u32, t as u32])
+                .collect();
+            vertices.sort();
+            vertices.dedup();
+            vertices
+        };
+
+        let envelope = crate::input::from_file("assets/envelope.in");
+        let tricon = get_triconnected_components(&envelope);
+        // ear tips are not in any separation pair
+        assert_eq!(tricon.separation_vertices(), vec![0, 1, 2, 3]);
+        assert_eq!(tricon.separation_vertices(), brute(&envelope));
+
+        for i in 0..100 {
+            let n = 3 + i / 10;
+            let in_graph = random_biconnected_graph(n, n + i % 7, i);
+            if in_graph.node_count() < 3 {
+                continue;
+            }
+            let tricon = get_triconnected_components(&in_graph);
+            assert_eq!(tricon.separation_vertices(), brute(&in_graph));
+        }
+    }
+
+    #[test]
+    fn test_triconnected_stats_envelope() {
+        let in_graph = crate::input::from_file("assets/envelope.in");
+        let tricon = get_triconnected_components(&in_graph);
+
+        // central cycle with four ears, each glued by a bond
+        assert_eq!(tricon.stats(), (5, 4, 0, 8));
+
+        // K4 is a single R-node
+        let in_graph = crate::input::from_str("0,1\n0,2\n0,3\n1,2\n1,3\n2,3\n");
+        let tricon = get_triconnected_components(&in_graph);
+        assert_eq!(tricon.stats(), (0, 0, 1, 0));
+    }
+
     #[cfg(all(test, not(debug_assertions)))]
     #[test]
     fn test_triconnected_exhaustive() {
@@ -1,4 +1,4 @@
-use crate::triconnected_blocks::{graph_internal::GraphInternal, outside_structures::EdgeType};
+use crate::triconnected_blocks::graph_internal::{EdgeType, GraphInternal};
 
 /// Modifies the adjacency lists of the given graph so that edges are sorted
 /// according to a custom phi value.
@@ -2,7 +2,19 @@ use std::mem::swap;
 
 use petgraph::visit::EdgeRef;
 
-use crate::{UnGraph, triconnected_blocks::outside_structures::EdgeType};
+use crate::UnGraph;
+
+/// Internal state of an edge during the decomposition.
+///
+/// `Tree` and `Back` have the same meaning as in the public [`crate::DFSEdgeLabel`],
+/// `Killed` marks edges which were already moved to some split component.
+/// Unvisited edges are represented by `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgeType {
+    Tree,
+    Back,
+    Killed,
+}
 
 /// Wrapper for `petgraph` implementation of a graph, because it forbids mutable access to edges brr...
 #[derive(Debug, Clone)]
@@ -7,6 +7,9 @@ use crate::triconnected_blocks::{
 ///
 /// This function modifies the graph in place by removing self-loops and merging duplicate edges,
 /// creating a `P` component for each set of duplicates. The adjacency list is updated accordingly.
+///
+/// Edges are grouped with two passes of counting sort, so it works in O(n + m)
+/// even for a bond of many parallel edges, `benches/triconnected.rs` measures it for growing bonds.
 pub fn handle_duplicate_edges(graph: &mut GraphInternal, split_components: &mut Vec<Component>) {
     // stable sort by second
     let mut cnt = vec![0; graph.n];
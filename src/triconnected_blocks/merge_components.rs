@@ -4,12 +4,20 @@ use crate::triconnected_blocks::outside_structures::{Component, ComponentType};
 ///
 /// Two components can be merged if and only if they are of the same type (excluding `R` nodes)
 /// and share a common virtual edge.
+///
+/// Merging is transitive, chains of bonds (or cycles) glued by virtual edges end up as a single component.
 pub fn merge_components(m: usize, split_components: &mut Vec<Component>) {
-    let mut edge_to_component = vec![0; m];
+    // each edge belongs to at most two components (exactly two for virtual edges)
+    let mut edge_to_components = vec![(usize::MAX, usize::MAX); m];
 
     for (i, component) in split_components.iter().enumerate() {
         for &eid in &component.edges {
-            edge_to_component[eid] = i;
+            let entry = &mut edge_to_components[eid];
+            if entry.0 == usize::MAX {
+                entry.0 = i;
+            } else {
+                entry.1 = i;
+            }
         }
     }
 
@@ -32,9 +40,11 @@ pub fn merge_components(m: usize, split_components: &mut Vec<Component>) {
         let mut j = 0;
         while j < collected_edges.len() {
             let eid = collected_edges[j];
-            let other_idx = edge_to_component[eid];
+            // the edge may come from an already merged component, so look at both of its components
+            let (first, second) = edge_to_components[eid];
+            let other_idx = if merged_already[first] { second } else { first };
 
-            if other_idx != i
+            if other_idx != usize::MAX
                 && !merged_already[other_idx]
                 && split_components[other_idx].comp_type == component.comp_type
             {
@@ -65,3 +75,73 @@ pub fn merge_components(m: usize, split_components: &mut Vec<Component>) {
     split_components.clear();
     split_components.extend(ret);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(comp_type: ComponentType, edges: Vec<usize>) -> Component {
+        let mut c = Component::new(comp_type);
+        c.edges = edges;
+        c
+    }
+
+    #[test]
+    fn test_merge_two_bonds() {
+        // four parallel edges 0..4 split by virtual edge 4
+        let mut split_components = vec![
+            component(ComponentType::P, vec![0, 1, 4]),
+            component(ComponentType::P, vec![4, 2, 3]),
+        ];
+        merge_components(5, &mut split_components);
+
+        assert_eq!(
+            split_components,
+            vec![component(ComponentType::P, vec![0, 1, 2, 3])]
+        );
+    }
+
+    #[test]
+    fn test_merge_chained_bonds() {
+        // real edges 0..5, virtual edges 5 and 6, the middle bond is the last one
+        let mut split_components = vec![
+            component(ComponentType::P, vec![0, 1, 5]),
+            component(ComponentType::P, vec![2, 3, 6]),
+            component(ComponentType::P, vec![5, 6, 4]),
+        ];
+        merge_components(7, &mut split_components);
+
+        assert_eq!(
+            split_components,
+            vec![component(ComponentType::P, vec![0, 1, 2, 3, 4])]
+        );
+    }
+
+    #[test]
+    fn test_merge_chained_cycles() {
+        // triangles glued along virtual edges 6 and 7 into a single cycle of real edges 0..6
+        let mut split_components = vec![
+            component(ComponentType::S, vec![0, 1, 6]),
+            component(ComponentType::S, vec![2, 3, 7]),
+            component(ComponentType::S, vec![6, 7, 4, 5]),
+            component(ComponentType::R, vec![8, 9, 10, 11, 12, 13]),
+        ];
+        merge_components(14, &mut split_components);
+
+        assert_eq!(split_components.len(), 2);
+        assert!(split_components.contains(&component(ComponentType::S, vec![0, 1, 2, 3, 4, 5])));
+    }
+
+    #[test]
+    fn test_component_equality_ignores_edge_order() {
+        let a = component(ComponentType::S, vec![3, 1, 2]);
+        let b = component(ComponentType::S, vec![1, 2, 3]);
+        assert_eq!(a, b);
+        assert_ne!(a, component(ComponentType::P, vec![1, 2, 3]));
+        assert_ne!(a, component(ComponentType::S, vec![1, 2, 4]));
+
+        let mut seen = crate::collections::HashSet::new();
+        assert!(seen.insert(a));
+        assert!(!seen.insert(b));
+    }
+}
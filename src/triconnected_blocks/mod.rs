@@ -6,4 +6,5 @@ pub(crate) mod palm_dfs;
 pub(crate) mod pathfinder;
 
 pub mod outside_structures;
+pub mod reduce;
 pub mod visualize;
@@ -1,22 +1,18 @@
 use crate::triconnected_blocks::graph_internal::GraphInternal;
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum EdgeType {
-    Tree,
-    Back,
-    Killed,
-}
-
 /// Represents the type of a component in the triconnected block decomposition.
 ///
 /// - `P`: Bond (parallel edges, k >= 3)
 /// - `S`: Cycle (simple cycle)
 /// - `R`: Triconnected component (rigid)
-#[derive(Debug, Clone, PartialEq, Copy)]
+/// - `MERGED`: Skeleton made by [`SPQRTree::merge_adjacent`](crate::spqr_blocks::outside_structures::SPQRTree::merge_adjacent),
+///   it is neither a cycle nor a bond and it is not necessarily triconnected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum ComponentType {
     P,      // bond
     S,      // triangle
     R,      // triconnected
+    MERGED, // not a canonical component, see merge_adjacent
     UNSURE, // used for initial state
 }
 
@@ -26,6 +22,7 @@ impl std::fmt::Display for ComponentType {
             ComponentType::P => write!(f, "P"),
             ComponentType::S => write!(f, "S"),
             ComponentType::R => write!(f, "R"),
+            ComponentType::MERGED => write!(f, "M"),
             &ComponentType::UNSURE => {
                 panic!();
             }
@@ -36,12 +33,29 @@ impl std::fmt::Display for ComponentType {
 /// Represents a component in the triconnected block decomposition.
 ///
 /// Contains a list of edges that belong to the component and its type.
+///
+/// Components are equal (and hash equally) if they have the same type and the same set of edges,
+/// regardless of the order of `edges`.
 #[derive(Debug, Clone)]
 pub struct Component {
     pub edges: Vec<usize>,
     pub comp_type: ComponentType,
 }
 
+impl PartialEq for Component {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical_key() == other.canonical_key()
+    }
+}
+
+impl Eq for Component {}
+
+impl std::hash::Hash for Component {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.canonical_key().hash(state);
+    }
+}
+
 impl Component {
     pub fn new(comp_type: ComponentType) -> Self {
         Self {
@@ -50,6 +64,13 @@ impl Component {
         }
     }
 
+    /// Returns the type and sorted edges of the component, identifying it up to the order of edges.
+    pub fn canonical_key(&self) -> (ComponentType, Vec<usize>) {
+        let mut edges = self.edges.clone();
+        edges.sort();
+        (self.comp_type, edges)
+    }
+
     pub fn push_edge(
         &mut self,
         eid: usize,
@@ -83,12 +104,99 @@ impl Component {
 ///
 /// - `comp`: List of components in the triconnected decomposition.
 /// - `edges`: List of edges in the graph. Also contains the virtual edges created during the splitting process.
+///   Real edges come first and keep indices of the input graph, i.e. `edges[i]` is the edge `EdgeIndex::new(i)`
+///   (so for a graph from [`crate::input::from_str`], the edge from its `i`-th edge line that is not a self-loop),
+///   virtual edges follow.
 /// - `is_real`: Indicates if an edge is a real edge in the original graph.
 /// - `to_split`: Maps edges to their corresponding split components. Virtual edges are mapped to `None`.
-#[derive(Debug, Clone)]
+///
+/// Decompositions are equal if all fields are equal, components are compared in order, each up to the order of its edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TriconnectedComponents {
     pub comp: Vec<Component>,
     pub edges: Vec<(usize, usize)>,
     pub is_real: Vec<bool>,
     pub to_split: Vec<Option<usize>>,
 }
+
+impl TriconnectedComponents {
+    /// Returns indices of edges of the input graph (`EdgeIndex::index()`) owned by component `comp`, in increasing order.
+    ///
+    /// It is the inverse of `to_split`: real edges keep indices of the input graph, virtual edges are skipped.
+    pub fn original_edges_of(&self, comp: usize) -> Vec<usize> {
+        let mut edges: Vec<usize> = self.comp[comp]
+            .edges
+            .iter()
+            .copied()
+            .filter(|&eid| self.is_real[eid])
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    /// Returns vertices (internal indices) belonging to at least one separation pair, in increasing order.
+    ///
+    /// These are the endpoints of virtual edges and all vertices of cycles (S-nodes) of length at least `4`,
+    /// as any two non-adjacent vertices of such a cycle separate it.
+    pub fn separation_vertices(&self) -> Vec<u32> {
+        let mut vertices = Vec::new();
+        for (eid, &(s, t)) in self.edges.iter().enumerate() {
+            if !self.is_real[eid] {
+                vertices.extend([s as u32, t as u32]);
+            }
+        }
+        for comp in &self.comp {
+            if comp.comp_type == ComponentType::S && comp.edges.len() >= 4 {
+                for &eid in &comp.edges {
+                    let (s, t) = self.edges[eid];
+                    vertices.extend([s as u32, t as u32]);
+                }
+            }
+        }
+        vertices.sort();
+        vertices.dedup();
+        vertices
+    }
+
+    /// Returns adjacency lists of components, two components are adjacent iff they share a virtual edge.
+    ///
+    /// This is the topology of the SPQR tree, see [`crate::spqr_tree::get_spqr_tree`] for the full structure.
+    pub fn component_adjacency(&self) -> Vec<Vec<usize>> {
+        let mut adj = vec![Vec::new(); self.comp.len()];
+
+        // for virtual edges it's the latter of two components containing the edge
+        let mut edge_to_component = vec![0; self.edges.len()];
+        for (i, component) in self.comp.iter().enumerate() {
+            for &eid in &component.edges {
+                edge_to_component[eid] = i;
+            }
+        }
+
+        for (i, component) in self.comp.iter().enumerate() {
+            for &eid in &component.edges {
+                let other = edge_to_component[eid];
+                if other != i {
+                    adj[i].push(other);
+                    adj[other].push(i);
+                }
+            }
+        }
+
+        adj
+    }
+
+    /// Returns counts of S-nodes, P-nodes, R-nodes and virtual edges, in this order.
+    ///
+    /// Each virtual edge is counted once, even though it belongs to two components.
+    pub fn stats(&self) -> (usize, usize, usize, usize) {
+        let count = |t: ComponentType| self.comp.iter().filter(|c| c.comp_type == t).count();
+        let virtual_edges = self.is_real.iter().filter(|&&real| !real).count();
+
+        (
+            count(ComponentType::S),
+            count(ComponentType::P),
+            count(ComponentType::R),
+            virtual_edges,
+        )
+    }
+}
@@ -1,6 +1,6 @@
 use std::mem;
 
-use crate::triconnected_blocks::{graph_internal::GraphInternal, outside_structures::EdgeType};
+use crate::triconnected_blocks::graph_internal::{EdgeType, GraphInternal};
 
 fn dfs(u: usize, time: &mut usize, graph: &mut GraphInternal) {
     graph.num[u] = *time;
@@ -66,7 +66,27 @@ fn dfs(u: usize, time: &mut usize, graph: &mut GraphInternal) {
 pub fn run_palm_dfs(graph: &mut GraphInternal, root: usize) {
     let mut time = 0;
     dfs(root, &mut time, graph);
+    orient_edges(graph);
+}
+
+/// Computes the palm forest of a possibly disconnected graph, see [`run_palm_dfs`].
+///
+/// DFS is started from every vertex not visited yet, in order of internal indices, so preorder numbers
+/// are unique across the whole forest. Returns the roots, their `par` is `None`.
+pub fn run_palm_forest(graph: &mut GraphInternal) -> Vec<usize> {
+    let mut time = 0;
+    let mut roots = vec![];
+    for u in 0..graph.n {
+        if graph.num[u] == usize::MAX {
+            roots.push(u);
+            dfs(u, &mut time, graph);
+        }
+    }
+    orient_edges(graph);
+    roots
+}
 
+fn orient_edges(graph: &mut GraphInternal) {
     // now that for each edge we know its type, we can assure that edges in `edges` always point from source to target
     for (eid, edge) in graph.edges.iter_mut().enumerate() {
         let (s, t) = (edge.0, edge.1);
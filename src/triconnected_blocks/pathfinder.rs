@@ -1,4 +1,4 @@
-use crate::triconnected_blocks::{graph_internal::GraphInternal, outside_structures::EdgeType};
+use crate::triconnected_blocks::graph_internal::{EdgeType, GraphInternal};
 
 fn dfs(
     root: usize,
@@ -0,0 +1,480 @@
+//! Reduction of a graph used to find separation pairs with Tsin's tri-edge-connectivity algorithm.
+//!
+//! Reference: <https://dl.acm.org/doi/pdf/10.5555/1862776.1862783>
+use crate::UnGraph;
+use petgraph::visit::{EdgeRef, IntoNodeReferences};
+
+/// Adjacency lists (edge ids), edge list and tree-edge flags of a reduced graph, see [`reduced_graph`].
+pub type ReducedGraph = (Vec<Vec<usize>>, Vec<(usize, usize)>, Vec<bool>);
+
+/// Returns the reduced graph on which Tsin's tri-edge-connectivity algorithm is run
+/// to find separation pairs, for inspection.
+///
+/// The graph is traversed by DFS and every vertex with more than one non-tree edge is replaced
+/// by a path of tree edges through fake vertices, each of them keeping one of the non-tree edges
+/// (ordered by preorder numbers of their other endpoints). A vertex whose own back edges reach
+/// higher than the ones of its subtree first moves them to a new fake child.
+/// So in the reduced graph every vertex has at most one non-tree edge.
+///
+/// Returns `(adj, edges, is_tree_edge)`: `adj[v]` are ids of edges incident to `v`,
+/// `edges[e]` are endpoints of edge `e` and `is_tree_edge[e]` tells if it is a DFS tree edge.
+/// Vertices `0..n` are the original ones (internal indices), the rest are fake vertices,
+/// original vertices that were replaced are left without edges.
+/// The first `m` edges are the edges of the graph, in the same order, the rest are added tree edges.
+///
+/// Input should be biconnected. DFS is started from every vertex not visited yet, in order
+/// of internal indices, so a connected graph gets one DFS tree rooted at index 0.
+///
+/// This is exactly the graph built by the reduction, only the separation pairs and DFS data are dropped.
+pub fn reduced_graph(graph: &UnGraph) -> ReducedGraph {
+    let (adj, edges, _, is_tree_edge, ..) = reduce(graph);
+    (adj, edges, is_tree_edge)
+}
+
+/// Returns the reduced graph (see [`reduced_graph`]) in DOT format.
+///
+/// Original vertices show their labels, fake vertices are gray and named `f<index>`.
+/// Tree edges are solid and other edges are dashed.
+///
+/// Intended to be used with `dot`.
+pub fn draw_reduced_graph(graph: &UnGraph) -> String {
+    let n = graph.node_count();
+    let (adj, edges, is_tree_edge) = reduced_graph(graph);
+
+    let mut output = String::from("graph {\n");
+    output.push_str("  node [shape=circle, style=filled, fillcolor=lightblue];\n");
+    for u in 0..adj.len() {
+        if u < n {
+            if !adj[u].is_empty() {
+                output.push_str(&format!(
+                    "  {} [label=\"{}\"];\n",
+                    u,
+                    graph[petgraph::graph::NodeIndex::new(u)]
+                ));
+            }
+        } else {
+            output.push_str(&format!("  {} [label=\"f{}\", fillcolor=gray];\n", u, u));
+        }
+    }
+    for (e, &(u, v)) in edges.iter().enumerate() {
+        let style = if is_tree_edge[e] { "solid" } else { "dashed" };
+        output.push_str(&format!("  {} -- {} [style={}];\n", u, v, style));
+    }
+    output.push_str("}\n");
+    output
+}
+
+fn new_vertex(
+    graph: &mut Vec<Vec<usize>>,
+    preorder: &mut Vec<usize>,
+    low1: &mut Vec<usize>,
+) -> usize {
+    let new_index = graph.len();
+    graph.push(Vec::new());
+    preorder.push(usize::MAX);
+    low1.push(usize::MAX);
+    new_index
+}
+
+fn add_edge(
+    edge_list: &mut Vec<(usize, usize)>,
+    is_tree_edge: &mut Vec<bool>,
+    graph: &mut [Vec<usize>],
+    u: usize,
+    v: usize,
+    is_tree: bool,
+) -> usize {
+    edge_list.push((u, v));
+    is_tree_edge.push(is_tree);
+    let eid = edge_list.len() - 1;
+    graph[u].push(eid);
+    graph[v].push(eid);
+    eid
+}
+
+fn move_edges(
+    u: usize,
+    u_fake: usize,
+    edge_list: &mut [(usize, usize)],
+    is_tree_edge: &[bool],
+    graph: &mut [Vec<usize>],
+    preorder: &[usize],
+) {
+    let mut i = 0;
+    while i < graph[u].len() {
+        let eid = graph[u][i];
+        let v = edge_list[eid].0 ^ edge_list[eid].1 ^ u;
+        if preorder[v] < preorder[u] && !is_tree_edge[eid] {
+            // a back edge, move to u_fake
+            if u == edge_list[eid].0 {
+                edge_list[eid].0 = u_fake;
+            } else {
+                edge_list[eid].1 = u_fake;
+            }
+            graph[u_fake].push(eid);
+            graph[u].swap_remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+fn reduce_vertex(
+    edge_list: &mut Vec<(usize, usize)>,
+    is_tree_edge: &mut Vec<bool>,
+    graph: &mut Vec<Vec<usize>>,
+    u: usize,
+    parent: Option<usize>,
+    preorder: &mut Vec<usize>,
+    low1: &mut Vec<usize>,
+) {
+    #[cfg(feature = "radsort")]
+    radsort::sort_by_key(&mut graph[u], |&eid| {
+        let v = edge_list[eid].0 ^ edge_list[eid].1 ^ u;
+        -(preorder[v] as isize)
+    });
+    #[cfg(not(feature = "radsort"))]
+    graph[u].sort_unstable_by_key(|&eid| {
+        let v = edge_list[eid].0 ^ edge_list[eid].1 ^ u;
+        std::cmp::Reverse(preorder[v])
+    });
+
+    // Let a_1, ..., a_k be the sequence of nodes such that \exists (u, u_i) \in non-tree-edges. (sorted by preorder)
+    // Let b_1, ..., b_k be the sequence of nodes such that \exists (u_i, u) \in non-tree-edges. (...)
+    // We replace u with a tree-path fake(a_k) -- ... -- fake(a_1) -- fake(b_k) -- ... -- fake(b_1).
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    let mut children = Vec::new();
+    for &eid in &graph[u] {
+        if is_tree_edge[eid] {
+            if Some(eid) != parent {
+                children.push(eid);
+            }
+            continue;
+        }
+        let v = edge_list[eid].0 ^ edge_list[eid].1 ^ u;
+        if preorder[v] < preorder[u] {
+            a.push(eid);
+        } else {
+            b.push(eid);
+        }
+    }
+
+    if a.len() + b.len() <= 1 {
+        // no need to reduce
+        return;
+    }
+
+    let mut first_created = usize::MAX;
+    let mut last_created = usize::MAX;
+
+    for &eid in a.iter().chain(b.iter()) {
+        let fake = new_vertex(graph, preorder, low1);
+
+        if last_created != usize::MAX {
+            add_edge(edge_list, is_tree_edge, graph, last_created, fake, true);
+        }
+
+        if first_created == usize::MAX {
+            first_created = fake;
+        }
+        last_created = fake;
+
+        if u == edge_list[eid].0 {
+            edge_list[eid].0 = fake;
+        } else {
+            edge_list[eid].1 = fake;
+        }
+        graph[fake].push(eid);
+
+        low1[fake] = low1[u];
+        preorder[fake] = preorder[u];
+    }
+
+    if let Some(p) = parent {
+        if u == edge_list[p].0 {
+            edge_list[p].0 = first_created;
+        } else {
+            edge_list[p].1 = first_created;
+        }
+        graph[first_created].push(p);
+    }
+
+    for eid in children {
+        if edge_list[eid].0 == u {
+            edge_list[eid].0 = last_created;
+        } else {
+            edge_list[eid].1 = last_created;
+        }
+        graph[last_created].push(eid);
+    }
+
+    graph[u].clear();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dfs(
+    edge_list: &mut Vec<(usize, usize)>,
+    is_tree_edge: &mut Vec<bool>,
+    graph: &mut Vec<Vec<usize>>,
+    root: usize,
+    u: usize,
+    time: &mut usize,
+    parent: Option<usize>,
+    parent_v: Option<usize>,
+    preorder: &mut Vec<usize>,
+    preorder_to_vertex: &mut Vec<usize>,
+    low1: &mut Vec<usize>,
+    low1_realizer: &mut Vec<usize>,
+    low2: &mut Vec<usize>,
+    split_pairs: &mut Vec<(usize, usize)>,
+    subsz: &mut Vec<usize>,
+    par: &mut Vec<usize>,
+) {
+    preorder[u] = *time;
+    low1[u] = *time;
+    low2[u] = *time;
+    preorder_to_vertex[*time] = u;
+    *time += 1;
+    low1_realizer[u] = u;
+    subsz[u] = 1;
+
+    let mut min_child_low = (usize::MAX, usize::MAX); // (low1, low1_realizer)
+
+    let edge_ids: Vec<usize> = graph[u].clone(); // borrow checker workaround
+    for &eid in &edge_ids {
+        let v = edge_list[eid].0 ^ edge_list[eid].1 ^ u;
+
+        if Some(eid) == parent {
+            continue;
+        }
+        if preorder[v] == usize::MAX {
+            is_tree_edge[eid] = true;
+            par[v] = u;
+            dfs(
+                edge_list,
+                is_tree_edge,
+                graph,
+                root,
+                v,
+                time,
+                Some(eid),
+                Some(u),
+                preorder,
+                preorder_to_vertex,
+                low1,
+                low1_realizer,
+                low2,
+                split_pairs,
+                subsz,
+                par,
+            );
+            subsz[u] += subsz[v];
+            min_child_low = min_child_low.min((low1[v], low1_realizer[v]));
+
+            if low1[v] < low1[u] {
+                low2[u] = low1[u].min(low2[v]);
+                low1[u] = low1[v];
+                low1_realizer[u] = low1_realizer[v];
+            } else if low1[v] == low1[u] {
+                low2[u] = low2[u].min(low2[v]);
+            } else {
+                low2[u] = low2[u].min(low1[v]);
+            }
+        } else if preorder[v] < preorder[u] {
+            // a back edge
+            if preorder[v] < low1[u] {
+                low2[u] = low1[u];
+                low1[u] = preorder[v];
+                low1_realizer[u] = v;
+            } else if preorder[v] > low1[u] {
+                low2[u] = low2[u].min(preorder[v]);
+            }
+        }
+    }
+
+    let x = low1_realizer[u];
+
+    if let Some(parent_v_idx) = parent_v
+        && x != parent_v_idx
+        && low2[u] >= preorder[parent_v_idx]
+        && (low1[u] != preorder[root] || par[parent_v_idx] != root)
+    {
+        split_pairs.push((x.min(parent_v_idx), x.max(parent_v_idx)));
+    }
+
+    if min_child_low.0 != usize::MAX && min_child_low.0 != low1[u] {
+        // move the back-edges of u to a newly created child
+        let u_fake = new_vertex(graph, preorder, low1);
+        preorder[u_fake] = preorder[u];
+        low1[u_fake] = low1[u];
+        move_edges(u, u_fake, edge_list, is_tree_edge, graph, preorder);
+
+        let to_fake = add_edge(edge_list, is_tree_edge, graph, u, u_fake, true);
+
+        reduce_vertex(
+            edge_list,
+            is_tree_edge,
+            graph,
+            u_fake,
+            Some(to_fake),
+            preorder,
+            low1,
+        );
+    }
+
+    reduce_vertex(edge_list, is_tree_edge, graph, u, parent, preorder, low1);
+}
+
+/// Reduced graph `(graph, edge_list, split_pairs, is_tree_edge, low1, preorder, preorder_to_vertex, subsz, par)`.
+pub(crate) type Reduced = (
+    Vec<Vec<usize>>,
+    Vec<(usize, usize)>,
+    Vec<(usize, usize)>,
+    Vec<bool>,
+    Vec<usize>,
+    Vec<usize>,
+    Vec<usize>,
+    Vec<usize>,
+    Vec<usize>,
+);
+
+/// Input: a biconnected graph
+/// Output: Type-A separation pairs and a reduced graph on which we'll run tsin's tri-edge-connectivity algorithm
+pub(crate) fn reduce(in_graph: &UnGraph) -> Reduced {
+    let graph_size = in_graph.node_references().size_hint().0;
+    let edge_count = in_graph.edge_references().size_hint().0;
+
+    let mut edge_list: Vec<(usize, usize)> = Vec::new();
+    let mut graph: Vec<Vec<usize>> = vec![Vec::new(); graph_size];
+    for (u, v) in in_graph
+        .edge_references()
+        .map(|e| (e.source().index(), e.target().index()))
+    {
+        edge_list.push((u, v));
+        graph[u].push(edge_list.len() - 1);
+        graph[v].push(edge_list.len() - 1);
+    }
+
+    let mut is_tree_edge = vec![false; edge_count];
+    let mut split_pairs = Vec::new(); // only type-A
+    let mut time = 1;
+    let mut preorder = vec![usize::MAX; graph_size];
+    let mut preorder_to_vertex = vec![usize::MAX; graph_size + 1];
+    let mut low1 = vec![usize::MAX; graph_size];
+    let mut low1_realizer = vec![usize::MAX; graph_size];
+    let mut low2 = vec![usize::MAX; graph_size];
+    let mut subsz = vec![0; graph_size]; // size of the subtree rooted at each vertex
+    let mut par = vec![usize::MAX; graph_size];
+
+    for u in 0..graph_size {
+        if preorder[u] == usize::MAX {
+            dfs(
+                &mut edge_list,
+                &mut is_tree_edge,
+                &mut graph,
+                u,
+                u,
+                &mut time,
+                None,
+                None,
+                &mut preorder,
+                &mut preorder_to_vertex,
+                &mut low1,
+                &mut low1_realizer,
+                &mut low2,
+                &mut split_pairs,
+                &mut subsz,
+                &mut par,
+            );
+        }
+    }
+
+    (
+        graph,
+        edge_list,
+        split_pairs,
+        is_tree_edge,
+        low1,
+        preorder,
+        preorder_to_vertex,
+        subsz,
+        par,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::random_graphs::random_biconnected_graph;
+
+    /// Checks that the reduced graph is the graph with vertices split into trees of fake vertices.
+    fn check_reduction(graph: &UnGraph) {
+        let n = graph.node_count();
+        let m = graph.edge_count();
+        let (adj, edges, is_tree_edge) = reduced_graph(graph);
+        assert_eq!(edges.len(), is_tree_edge.len());
+
+        // every vertex has at most one non-tree edge and `adj` agrees with `edges`
+        for (v, around) in adj.iter().enumerate() {
+            assert!(around.iter().filter(|&&e| !is_tree_edge[e]).count() <= 1);
+            for &e in around {
+                assert!(edges[e].0 == v || edges[e].1 == v);
+            }
+        }
+        let degree_sum: usize = adj.iter().map(|a| a.len()).sum();
+        assert_eq!(degree_sum, 2 * edges.len());
+
+        // tree edges form a spanning tree of the vertices with edges
+        let used = adj.iter().filter(|a| !a.is_empty()).count();
+        assert_eq!(is_tree_edge.iter().filter(|&&t| t).count(), used - 1);
+
+        // contracting the added edges gives back the graph, endpoints of an edge are replaced in place
+        let mut root: Vec<usize> = (0..adj.len()).collect();
+        fn find(root: &mut [usize], v: usize) -> usize {
+            if root[v] != v {
+                root[v] = find(root, root[v]);
+            }
+            root[v]
+        }
+        for &(u, v) in &edges[m..] {
+            let (ru, rv) = (find(&mut root, u), find(&mut root, v));
+            root[ru] = rv;
+        }
+        let mut owner = vec![None; adj.len()];
+        for e in graph.edge_references() {
+            let (s, t) = edges[e.id().index()];
+            for (x, original) in [(s, e.source().index()), (t, e.target().index())] {
+                let r = find(&mut root, x);
+                assert_eq!(*owner[r].get_or_insert(original), original);
+            }
+        }
+        let mut owned: Vec<usize> = owner.into_iter().flatten().collect();
+        owned.sort();
+        assert!(owned.windows(2).all(|w| w[0] != w[1]));
+        assert_eq!(owned.len(), n);
+    }
+
+    #[test]
+    fn test_reduced_graph_diamond() {
+        // DFS from 0 leaves two back edges at 0 (from 3 and 1), so it is replaced by a path of two fake vertices
+        let graph = crate::input::from_str("0,2\n0,3\n0,1\n2,3\n3,1\n");
+        check_reduction(&graph);
+
+        let (adj, edges, is_tree_edge) = reduced_graph(&graph);
+        assert_eq!(adj.len(), 4 + 2);
+        assert_eq!(edges.len(), 5 + 1);
+        assert_eq!(is_tree_edge.iter().filter(|&&t| t).count(), 4);
+        assert!(adj[0].is_empty());
+        assert!(draw_reduced_graph(&graph).contains("  4 -- 5 [style=solid];\n"));
+    }
+
+    #[test]
+    fn test_reduced_graph_light() {
+        for i in 0..200 {
+            let n = 3 + i / 10;
+            let graph = random_biconnected_graph(n, n + i % 11, i);
+            check_reduction(&graph);
+        }
+    }
+}
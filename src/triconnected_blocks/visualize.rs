@@ -1,8 +1,23 @@
-use crate::triconnected_blocks::outside_structures::{ComponentType, TriconnectedComponents};
+use crate::triconnected_blocks::outside_structures::{
+    Component, ComponentType, TriconnectedComponents,
+};
 use std::fmt::Write;
 
+/// Returns the prefix used to name clusters of the component: its type, or `Q` for a component
+/// made of a single real edge (the decomposition itself never creates such components, but they may
+/// appear in hand-made or coarsened trees).
+pub(crate) fn component_prefix(comp: &Component, tricon: &TriconnectedComponents) -> String {
+    if comp.edges.len() == 1 && tricon.is_real[comp.edges[0]] {
+        "Q".to_string()
+    } else {
+        comp.comp_type.to_string()
+    }
+}
+
 /// Given a `TriconnectedComponents` structure, this function generates a
 /// Graphviz DOT representation of the triconnected components of a graph.
+///
+/// Components with a single real edge are drawn as Q-nodes, with box-shaped vertices.
 pub fn visualize_triconnected(tricon: &TriconnectedComponents) -> String {
     let mut output = String::new();
 
@@ -54,7 +69,14 @@ pub fn visualize_triconnected(tricon: &TriconnectedComponents) -> String {
     }
 
     for (i, comp) in tricon.comp.iter().enumerate() {
+        let is_q = component_prefix(comp, tricon) == "Q";
         let (prefix, label, fillcolor, nodecolor) = match comp.comp_type {
+            _ if is_q => (
+                "Q",
+                format!("Q-component ({})", i + 1),
+                "#ffffe6",
+                "#ffffcc",
+            ),
             ComponentType::R => (
                 "R",
                 format!("R-component ({})", i + 1),
@@ -73,6 +95,12 @@ pub fn visualize_triconnected(tricon: &TriconnectedComponents) -> String {
                 "#ffe6e6",
                 "#ffcccc",
             ),
+            ComponentType::MERGED => (
+                "M",
+                format!("M-component ({})", i + 1),
+                "#f0f0f0",
+                "#dddddd",
+            ),
             _ => {
                 panic!();
             }
@@ -97,11 +125,12 @@ pub fn visualize_triconnected(tricon: &TriconnectedComponents) -> String {
         for v in nodes {
             writeln!(
                 output,
-                "    {}{}_{} [label=\"{}\", shape=circle, fillcolor=\"{}\", style=filled];",
+                "    {}{}_{} [label=\"{}\", shape={}, fillcolor=\"{}\", style=filled];",
                 prefix,
                 i + 1,
                 v,
                 v,
+                if is_q { "box" } else { "circle" },
                 nodecolor
             )
             .unwrap();
@@ -142,3 +171,207 @@ pub fn visualize_triconnected(tricon: &TriconnectedComponents) -> String {
     writeln!(output, "}}").unwrap();
     output
 }
+
+/// Alternative to [`visualize_triconnected`] which draws the input graph only once.
+///
+/// Every component is a cluster, a vertex is placed in the cluster of the first component containing it
+/// (Graphviz can't put a node in more clusters). Real edges are solid, virtual edges of split pairs
+/// are drawn once, dashed, between the clusters sharing them. Components without own vertices
+/// get an invisible anchor node, so each of them is rendered.
+pub fn visualize_triconnected_clustered(tricon: &TriconnectedComponents) -> String {
+    let mut output = String::new();
+
+    writeln!(output, "graph components {{").unwrap();
+    writeln!(output, "  graph [splines=true, compound=true];").unwrap();
+    writeln!(output, "  node [fontname=\"Helvetica\"];").unwrap();
+    writeln!(output).unwrap();
+
+    let n = tricon
+        .edges
+        .iter()
+        .map(|&(a, b)| a.max(b) + 1)
+        .max()
+        .unwrap_or(0);
+    let mut home = vec![usize::MAX; n];
+    for (i, comp) in tricon.comp.iter().enumerate() {
+        for &eid in &comp.edges {
+            let (from, to) = tricon.edges[eid];
+            for v in [from, to] {
+                if home[v] == usize::MAX {
+                    home[v] = i;
+                }
+            }
+        }
+    }
+
+    for (i, comp) in tricon.comp.iter().enumerate() {
+        let (prefix, fillcolor, nodecolor) = match comp.comp_type {
+            ComponentType::R => ("R", "#e6e6ff", "#ccccff"),
+            ComponentType::P => ("P", "#e6ffe6", "#ccffcc"),
+            ComponentType::S => ("S", "#ffe6e6", "#ffcccc"),
+            ComponentType::MERGED => ("M", "#f0f0f0", "#dddddd"),
+            _ => {
+                panic!();
+            }
+        };
+
+        writeln!(output, "  subgraph cluster_{}{} {{", prefix, i + 1).unwrap();
+        writeln!(output, "    label=\"{}-component ({})\";", prefix, i + 1).unwrap();
+        writeln!(output, "    style=filled; fillcolor=\"{}\";", fillcolor).unwrap();
+
+        let own: Vec<usize> = (0..n).filter(|&v| home[v] == i).collect();
+        if own.is_empty() {
+            writeln!(output, "    anchor_{} [shape=point, style=invis];", i + 1).unwrap();
+        }
+        for v in own {
+            writeln!(
+                output,
+                "    {} [label=\"{}\", shape=circle, fillcolor=\"{}\", style=filled];",
+                v, v, nodecolor
+            )
+            .unwrap();
+        }
+
+        writeln!(output, "  }}").unwrap();
+        writeln!(output).unwrap();
+    }
+
+    // Edges, virtual ones only once although they belong to two components
+    let mut drawn = vec![false; tricon.edges.len()];
+    for comp in &tricon.comp {
+        for &eid in &comp.edges {
+            if drawn[eid] {
+                continue;
+            }
+            drawn[eid] = true;
+
+            let (from, to) = tricon.edges[eid];
+            writeln!(
+                output,
+                "  {} -- {} [label=\"{}\"{}];",
+                from,
+                to,
+                eid,
+                if tricon.is_real[eid] {
+                    ", color=black"
+                } else {
+                    ", style=dashed, color=gray"
+                }
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(output, "}}").unwrap();
+    output
+}
+
+impl Component {
+    /// Generates a Graphviz DOT representation of the skeleton of this component only.
+    ///
+    /// `tricon` is the decomposition the component comes from, it is needed to resolve edge ids.
+    /// Styling is the same as in [`visualize_triconnected`], virtual edges are dashed.
+    pub fn to_dot(&self, tricon: &TriconnectedComponents) -> String {
+        let mut output = String::new();
+
+        let (label, nodecolor) = match self.comp_type {
+            ComponentType::R => ("R-component", "#ccccff"),
+            ComponentType::P => ("P-component", "#ccffcc"),
+            ComponentType::S => ("S-component", "#ffcccc"),
+            ComponentType::MERGED => ("M-component", "#dddddd"),
+            _ => {
+                panic!();
+            }
+        };
+
+        writeln!(output, "graph component {{").unwrap();
+        writeln!(output, "  label=\"{}\";", label).unwrap();
+        writeln!(output, "  node [fontname=\"Helvetica\"];").unwrap();
+        writeln!(output).unwrap();
+
+        let mut nodes = Vec::new();
+        for &eid in &self.edges {
+            let (from, to) = tricon.edges[eid];
+            if !nodes.contains(&from) {
+                nodes.push(from);
+            }
+            if !nodes.contains(&to) {
+                nodes.push(to);
+            }
+        }
+
+        // Nodes
+        for v in nodes {
+            writeln!(
+                output,
+                "  {} [label=\"{}\", shape=circle, fillcolor=\"{}\", style=filled];",
+                v, v, nodecolor
+            )
+            .unwrap();
+        }
+        writeln!(output).unwrap();
+
+        // Edges
+        for &eid in &self.edges {
+            let (from, to) = tricon.edges[eid];
+            writeln!(
+                output,
+                "  {} -- {} [label=\"{}\"{}];",
+                from,
+                to,
+                eid,
+                if tricon.is_real[eid] {
+                    ", color=black"
+                } else {
+                    ", style=dashed, color=gray"
+                }
+            )
+            .unwrap();
+        }
+
+        writeln!(output, "}}").unwrap();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{input::from_str, triconnected::get_triconnected_components};
+
+    use super::*;
+
+    #[test]
+    fn test_component_to_dot_r_node() {
+        // K4 with an ear on (0, 1), so there is one R-node with a virtual edge
+        let graph = from_str("0,1\n0,2\n0,3\n1,2\n1,3\n2,3\n0,4\n4,1\n");
+        let tricon = get_triconnected_components(&graph);
+
+        let r_node = tricon
+            .comp
+            .iter()
+            .find(|c| c.comp_type == ComponentType::R)
+            .unwrap();
+        let dot = r_node.to_dot(&tricon);
+
+        assert!(dot.contains("label=\"R-component\""));
+        assert_eq!(dot.matches("shape=circle").count(), 4);
+        assert_eq!(dot.matches(" -- ").count(), 6);
+        assert_eq!(dot.matches("style=dashed").count(), 1);
+    }
+
+    #[test]
+    fn test_visualize_triconnected_clustered_envelope() {
+        let graph = crate::input::from_file("assets/envelope.in");
+        let tricon = get_triconnected_components(&graph);
+        let dot = visualize_triconnected_clustered(&tricon);
+
+        let (s, p, r, virtual_edges) = tricon.stats();
+        assert_eq!(dot.matches("subgraph cluster_").count(), s + p + r);
+        // every vertex is drawn once, every real edge is drawn once
+        assert_eq!(dot.matches("shape=circle").count(), graph.node_count());
+        assert_eq!(dot.matches("color=black").count(), graph.edge_count());
+        // virtual edges removed while merging components are not drawn
+        assert!(dot.matches("style=dashed").count() <= virtual_edges);
+        assert!(dot.matches("style=dashed").count() > 0);
+    }
+}
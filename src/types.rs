@@ -1,5 +1,11 @@
+use crate::collections::HashSet;
+use crate::input::require_simple;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use std::collections::HashMap;
+
 /// Enum representing the type of edge in a graph.
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 pub enum EdgeLabel {
     Real,
     Virtual,
@@ -37,3 +43,610 @@ impl std::fmt::Display for DFSEdgeLabel {
         }
     }
 }
+
+/// Returns the undirected graph of an embedding, e.g. the one returned by `is_planar`.
+///
+/// Embeddings store every edge as two directed half-edges `u -> v` and `v -> u`,
+/// these are collapsed into one undirected edge. Node and edge labels are preserved.
+pub fn undirected_of(graph: &DiGraph) -> UnGraph {
+    let mut g = UnGraph::new_undirected();
+    for &label in graph.node_weights() {
+        g.add_node(label);
+    }
+    for e in graph.edge_references() {
+        let (u, v) = (e.source(), e.target());
+        if u.index() < v.index() {
+            g.add_edge(u, v, *e.weight());
+        }
+    }
+    g
+}
+
+/// Returns the embedding of `graph` given by a rotation system, in the format returned by `is_planar`.
+///
+/// `rotation[u]` lists neighbors of `u` in their cyclic order around `u`, every edge `{u, v}` is emitted
+/// as half-edges `u -> v` and `v -> u`, so that `neighbors(u)` of the result iterates exactly `rotation[u]`.
+/// Node labels are preserved and half-edges take the label of the edge they come from.
+///
+/// Panics if `rotation` doesn't list every neighbor of every vertex or the graph is not simple.
+pub fn embed_with_rotation(graph: &UnGraph, rotation: &[Vec<usize>]) -> DiGraph {
+    if let Err(err) = require_simple(graph) {
+        panic!("{}", err);
+    }
+    assert_eq!(
+        rotation.len(),
+        graph.node_count(),
+        "Every vertex needs a rotation"
+    );
+
+    let mut embedding = DiGraph::new();
+    for &label in graph.node_weights() {
+        embedding.add_node(label);
+    }
+    for (u, rot) in rotation.iter().enumerate() {
+        let mut expected: Vec<usize> = graph
+            .neighbors(NodeIndex::new(u))
+            .map(|v| v.index())
+            .collect();
+        let mut listed = rot.clone();
+        expected.sort();
+        listed.sort();
+        assert_eq!(
+            listed, expected,
+            "Rotation of a vertex must list all its neighbors"
+        );
+
+        // petgraph iterates edges around a vertex in reversed order of insertion
+        for &v in rot.iter().rev() {
+            let e = graph
+                .find_edge(NodeIndex::new(u), NodeIndex::new(v))
+                .unwrap();
+            embedding.add_edge(NodeIndex::new(u), NodeIndex::new(v), graph[e]);
+        }
+    }
+    embedding
+}
+
+/// Collapses parallel edges into one, returns the simple graph and multiplicities of its edges.
+///
+/// Multiplicities are keyed by labels of endpoints `(min, max)`, so bonds can be restored later.
+/// Nodes keep their internal indices and each kept edge keeps the label of its first copy.
+pub fn simplify(graph: &UnGraph) -> (UnGraph, HashMap<(u32, u32), usize>) {
+    let mut g = UnGraph::new_undirected();
+    for &label in graph.node_weights() {
+        g.add_node(label);
+    }
+
+    let mut multiplicity = HashMap::new();
+    for e in graph.edge_references() {
+        let (a, b) = (graph[e.source()], graph[e.target()]);
+        let count = multiplicity.entry((a.min(b), a.max(b))).or_insert(0);
+        if *count == 0 {
+            g.add_edge(e.source(), e.target(), *e.weight());
+        }
+        *count += 1;
+    }
+
+    (g, multiplicity)
+}
+
+/// Returns the line graph: one node per edge (labeled with the edge index), two nodes are adjacent
+/// iff their edges share an endpoint.
+///
+/// For multigraphs parallel edges share two endpoints, but they are still connected by a single edge.
+pub fn line_graph(graph: &UnGraph) -> UnGraph {
+    let mut lg = UnGraph::new_undirected();
+    for e in graph.edge_indices() {
+        lg.add_node(e.index() as u32);
+    }
+
+    let mut added = HashSet::new();
+    for v in graph.node_indices() {
+        let incident: Vec<usize> = graph.edges(v).map(|e| e.id().index()).collect();
+        for (i, &a) in incident.iter().enumerate() {
+            for &b in &incident[i + 1..] {
+                if a != b && added.insert((a.min(b), a.max(b))) {
+                    lg.add_edge(NodeIndex::new(a), NodeIndex::new(b), EdgeLabel::Real);
+                }
+            }
+        }
+    }
+
+    lg
+}
+
+/// Returns edges as `(u, v, label)` with `u <= v` being node labels, sorted.
+///
+/// Unlike `edge_references()`, the order doesn't depend on the order in which edges were inserted,
+/// so it can be used wherever the output should be reproducible.
+pub fn sorted_edges(graph: &UnGraph) -> Vec<(u32, u32, EdgeLabel)> {
+    let mut edges: Vec<(u32, u32, EdgeLabel)> = graph
+        .edge_references()
+        .map(|e| {
+            let (a, b) = (graph[e.source()], graph[e.target()]);
+            (a.min(b), a.max(b), *e.weight())
+        })
+        .collect();
+    edges.sort();
+    edges
+}
+
+/// Returns the compressed sparse row (CSR) adjacency of the graph: row offsets and column indices.
+///
+/// Neighbors of `v` are `columns[offsets[v]..offsets[v + 1]]`, in the same order as `graph.neighbors(v)` yields them.
+/// Parallel edges appear multiple times, a self-loop appears once.
+pub fn to_csr(graph: &UnGraph) -> (Vec<usize>, Vec<usize>) {
+    let (offsets, adj) = to_csr_with_edges(graph);
+    (offsets, adj.into_iter().map(|(v, _)| v).collect())
+}
+
+/// Same as [`to_csr`], but each entry also holds the index of the edge leading to the neighbor.
+pub(crate) fn to_csr_with_edges(graph: &UnGraph) -> (Vec<usize>, Vec<(usize, usize)>) {
+    let n = graph.node_count();
+    let mut offsets = vec![0; n + 1];
+    for e in graph.edge_references() {
+        offsets[e.source().index() + 1] += 1;
+        if e.source() != e.target() {
+            offsets[e.target().index() + 1] += 1;
+        }
+    }
+    for v in 0..n {
+        offsets[v + 1] += offsets[v];
+    }
+
+    // petgraph yields outgoing edges first and then incoming ones (without self-loops),
+    // in both lists the most recently added edge comes first
+    let mut next = offsets.clone();
+    let mut adj = vec![(0, 0); offsets[n]];
+    let edges: Vec<_> = graph.edge_references().collect();
+    for e in edges.iter().rev() {
+        let s = e.source().index();
+        adj[next[s]] = (e.target().index(), e.id().index());
+        next[s] += 1;
+    }
+    for e in edges.iter().rev().filter(|e| e.source() != e.target()) {
+        let t = e.target().index();
+        adj[next[t]] = (e.source().index(), e.id().index());
+        next[t] += 1;
+    }
+
+    (offsets, adj)
+}
+
+/// Returns degrees of all vertices sorted in descending order.
+///
+/// Parallel edges are counted separately.
+pub fn degree_sequence(graph: &UnGraph) -> Vec<usize> {
+    let mut degrees: Vec<usize> = graph
+        .node_indices()
+        .map(|v| graph.neighbors(v).count())
+        .collect();
+    degrees.sort_by(|a, b| b.cmp(a));
+    degrees
+}
+
+/// Returns the minimum degree of the graph or `None` if it has no vertices.
+///
+/// Useful as a quick sanity check, e.g. biconnected graph on at least 3 vertices has minimum degree at least 2.
+pub fn min_degree(graph: &UnGraph) -> Option<usize> {
+    graph
+        .node_indices()
+        .map(|v| graph.neighbors(v).count())
+        .min()
+}
+
+/// Returns the maximum degree of the graph or `None` if it has no vertices.
+pub fn max_degree(graph: &UnGraph) -> Option<usize> {
+    graph
+        .node_indices()
+        .map(|v| graph.neighbors(v).count())
+        .max()
+}
+
+/// Returns the number of edges connecting vertices labeled `u` and `v`.
+///
+/// Returns `0` if there is no vertex with one of the labels.
+pub fn edge_multiplicity(graph: &UnGraph, u: u32, v: u32) -> usize {
+    let find = |label: u32| graph.node_indices().find(|&x| graph[x] == label);
+    match (find(u), find(v)) {
+        (Some(a), Some(b)) => graph.edges_connecting(a, b).count(),
+        _ => 0,
+    }
+}
+
+/// Removes the vertex labeled `label` together with all its incident edges.
+///
+/// `remove_node` of petgraph moves the last node into the freed index, so instead the graph is rebuilt:
+/// remaining nodes keep their labels and relative order, edges keep their labels and relative order.
+/// Does nothing if there is no vertex with the label.
+pub fn remove_node_keep_labels(graph: &mut UnGraph, label: u32) {
+    let Some(removed) = graph.node_indices().find(|&x| graph[x] == label) else {
+        return;
+    };
+
+    let mut g = UnGraph::with_capacity(graph.node_count() - 1, graph.edge_count());
+    let mut new_index = vec![None; graph.node_count()];
+    for v in graph.node_indices() {
+        if v != removed {
+            new_index[v.index()] = Some(g.add_node(graph[v]));
+        }
+    }
+    for e in graph.edge_references() {
+        if let (Some(a), Some(b)) = (new_index[e.source().index()], new_index[e.target().index()]) {
+            g.add_edge(a, b, *e.weight());
+        }
+    }
+
+    *graph = g;
+}
+
+/// Returns the `k`-core of the graph: its maximal induced subgraph with minimum degree at least `k`.
+///
+/// Vertices of degree less than `k` are peeled off one by one, which takes O(n + m) time.
+/// Parallel edges are counted separately, self-loops don't count. Remaining nodes keep their labels
+/// and relative order, as in [`remove_node_keep_labels`]. The result may be empty.
+pub fn k_core(graph: &UnGraph, k: usize) -> UnGraph {
+    let n = graph.node_count();
+    let mut degree = vec![0; n];
+    for e in graph.edge_references() {
+        if e.source() != e.target() {
+            degree[e.source().index()] += 1;
+            degree[e.target().index()] += 1;
+        }
+    }
+
+    let mut removed: Vec<bool> = degree.iter().map(|&d| d < k).collect();
+    let mut stack: Vec<usize> = (0..n).filter(|&v| removed[v]).collect();
+    while let Some(u) = stack.pop() {
+        for e in graph.edges(NodeIndex::new(u)) {
+            let v = if e.source().index() == u {
+                e.target()
+            } else {
+                e.source()
+            }
+            .index();
+            if v == u || removed[v] {
+                continue;
+            }
+            degree[v] -= 1;
+            if degree[v] < k {
+                removed[v] = true;
+                stack.push(v);
+            }
+        }
+    }
+
+    let mut core = UnGraph::new_undirected();
+    let mut new_index = vec![None; n];
+    for v in graph.node_indices() {
+        if !removed[v.index()] {
+            new_index[v.index()] = Some(core.add_node(graph[v]));
+        }
+    }
+    for e in graph.edge_references() {
+        if let (Some(a), Some(b)) = (new_index[e.source().index()], new_index[e.target().index()]) {
+            core.add_edge(a, b, *e.weight());
+        }
+    }
+    core
+}
+
+/// Returns a 2-coloring of the graph (`true`/`false` for the two sides) or `None` if it is not bipartite.
+///
+/// See [`odd_cycle`] for a witness of non-bipartiteness.
+pub fn is_bipartite(graph: &UnGraph) -> Option<Vec<bool>> {
+    two_coloring(graph).ok()
+}
+
+/// Returns vertices (internal indices) of an odd cycle in order, or `None` if the graph is bipartite.
+///
+/// Self-loops are odd cycles of length `1`.
+pub fn odd_cycle(graph: &UnGraph) -> Option<Vec<usize>> {
+    two_coloring(graph).err()
+}
+
+/// Colors the graph with DFS, on failure returns an odd cycle closed by the conflicting edge.
+fn two_coloring(graph: &UnGraph) -> Result<Vec<bool>, Vec<usize>> {
+    let n = graph.node_count();
+    let mut color = vec![false; n];
+    let mut depth = vec![usize::MAX; n];
+    let mut parent = vec![usize::MAX; n];
+
+    for root in 0..n {
+        if depth[root] != usize::MAX {
+            continue;
+        }
+        depth[root] = 0;
+
+        let mut stack = vec![root];
+        while let Some(u) = stack.pop() {
+            for v in graph.neighbors(NodeIndex::new(u)).map(|v| v.index()) {
+                if depth[v] == usize::MAX {
+                    depth[v] = depth[u] + 1;
+                    parent[v] = u;
+                    color[v] = !color[u];
+                    stack.push(v);
+                } else if color[v] == color[u] {
+                    // both endpoints have depths of the same parity, so tree paths to LCA and the edge form an odd cycle
+                    let (mut a, mut b) = (u, v);
+                    let (mut up, mut down) = (vec![], vec![]);
+                    while a != b {
+                        if depth[a] >= depth[b] {
+                            up.push(a);
+                            a = parent[a];
+                        } else {
+                            down.push(b);
+                            b = parent[b];
+                        }
+                    }
+                    up.push(a);
+                    up.extend(down.into_iter().rev());
+                    return Err(up);
+                }
+            }
+        }
+    }
+
+    Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_edges_insertion_order() {
+        let a = crate::input::from_str("3,1\n1,2\n2,3,v\n4,1\n1,2\n");
+        let b = crate::input::from_str("1,2\n1,4\n1,2\n3,2,v\n1,3\n");
+        assert_eq!(sorted_edges(&a), sorted_edges(&b));
+        assert_eq!(
+            sorted_edges(&a),
+            vec![
+                (1, 2, EdgeLabel::Real),
+                (1, 2, EdgeLabel::Real),
+                (1, 3, EdgeLabel::Real),
+                (1, 4, EdgeLabel::Real),
+                (2, 3, EdgeLabel::Virtual),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_embed_with_rotation() {
+        // square 0-1-2-3 with a virtual chord 0-2
+        let graph = crate::input::from_str("0,1\n1,2\n2,3\n3,0\n0,2,v\n");
+        let rotation = vec![vec![1, 2, 3], vec![2, 0], vec![3, 0, 1], vec![0, 2]];
+        let embedding = embed_with_rotation(&graph, &rotation);
+
+        assert_eq!(
+            embedding.node_weights().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(embedding.edge_count(), 2 * graph.edge_count());
+        for (u, rot) in rotation.iter().enumerate() {
+            let order: Vec<usize> = embedding
+                .neighbors(NodeIndex::new(u))
+                .map(|v| v.index())
+                .collect();
+            assert_eq!(&order, rot);
+        }
+        let e = embedding
+            .find_edge(NodeIndex::new(2), NodeIndex::new(0))
+            .unwrap();
+        assert_eq!(embedding[e], EdgeLabel::Virtual);
+
+        // the chord splits the inner face into two triangles
+        let faces = crate::drawing_blocks::faces::get_faces(&embedding);
+        let mut sizes: Vec<usize> = faces.iter().map(|f| f.order.len()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![3, 3, 4]);
+    }
+
+    #[test]
+    fn test_k_core() {
+        let path = crate::input::from_str("0,1\n1,2\n2,3\n3,4\n");
+        let core = k_core(&path, 2);
+        assert_eq!(core.node_count(), 0);
+        assert_eq!(core.edge_count(), 0);
+
+        let cycle = crate::input::from_str("0,1\n1,2\n2,3\n3,4\n4,0\n");
+        assert_eq!(sorted_edges(&k_core(&cycle, 2)), sorted_edges(&cycle));
+        assert_eq!(k_core(&cycle, 3).node_count(), 0);
+
+        let k4 = crate::input::from_str("0,1\n0,2\n0,3\n1,2\n1,3\n2,3\n");
+        assert_eq!(sorted_edges(&k_core(&k4, 3)), sorted_edges(&k4));
+
+        // K4 with a pendant path and a triangle attached, only K4 survives in the 3-core
+        let graph =
+            crate::input::from_str("0,1\n0,2\n0,3\n1,2\n1,3\n2,3\n3,4\n4,5\n0,6\n6,7\n7,0\n");
+        let core = k_core(&graph, 3);
+        assert_eq!(
+            core.node_weights().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(sorted_edges(&core), sorted_edges(&k4));
+        assert_eq!(k_core(&graph, 2).node_count(), 6);
+        assert_eq!(k_core(&graph, 0).node_count(), graph.node_count());
+    }
+
+    #[test]
+    fn test_remove_node_keep_labels() {
+        let mut graph = crate::input::from_str("10,20\n20,30\n30,40\n40,10\n20,40,v\n10,30\n");
+        remove_node_keep_labels(&mut graph, 20);
+        assert_eq!(
+            graph.node_weights().copied().collect::<Vec<_>>(),
+            vec![10, 30, 40]
+        );
+        assert_eq!(
+            sorted_edges(&graph),
+            vec![
+                (10, 30, EdgeLabel::Real),
+                (10, 40, EdgeLabel::Real),
+                (30, 40, EdgeLabel::Real),
+            ]
+        );
+
+        // unknown label is a no-op
+        remove_node_keep_labels(&mut graph, 20);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+    }
+
+    #[test]
+    fn test_is_bipartite_even_cycle() {
+        let graph = crate::input::from_str("0,1\n1,2\n2,3\n3,4\n4,5\n5,0\n");
+        let coloring = is_bipartite(&graph).unwrap();
+        for e in graph.edge_references() {
+            assert_ne!(coloring[e.source().index()], coloring[e.target().index()]);
+        }
+        assert_eq!(odd_cycle(&graph), None);
+    }
+
+    #[test]
+    fn test_is_bipartite_odd_cycles() {
+        let triangle = crate::input::from_str("0,1\n1,2\n2,0\n");
+        let pentagon_with_tail = crate::input::from_str("0,1\n1,2\n2,3\n3,4\n4,0\n4,5\n5,6\n");
+
+        for graph in [triangle, pentagon_with_tail] {
+            assert_eq!(is_bipartite(&graph), None);
+
+            let cycle = odd_cycle(&graph).unwrap();
+            assert_eq!(cycle.len() % 2, 1);
+            for i in 0..cycle.len() {
+                let (a, b) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+                assert!(graph.contains_edge(NodeIndex::new(a), NodeIndex::new(b)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_csr_matches_neighbors() {
+        use crate::testing::random_graphs::random_connected_graph;
+
+        for seed in 0..20 {
+            let graph = random_connected_graph(30, 80, seed);
+            let (offsets, columns) = to_csr(&graph);
+            assert_eq!(offsets.len(), graph.node_count() + 1);
+            for v in graph.node_indices() {
+                let expected: Vec<usize> = graph.neighbors(v).map(|u| u.index()).collect();
+                assert_eq!(
+                    columns[offsets[v.index()]..offsets[v.index() + 1]],
+                    expected
+                );
+            }
+        }
+
+        // parallel edges and a self-loop
+        let graph = crate::input::from_str("0,1\n1,0\n1,1\n1,2\n");
+        let (offsets, columns) = to_csr(&graph);
+        for v in graph.node_indices() {
+            let expected: Vec<usize> = graph.neighbors(v).map(|u| u.index()).collect();
+            assert_eq!(
+                columns[offsets[v.index()]..offsets[v.index() + 1]],
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_simplify_bond_and_triangle() {
+        // bond of 3 edges between 1 and 2, triangle 2 - 3 - 4
+        let graph = crate::input::from_str("1,2\n2,1\n1,2\n2,3\n3,4\n4,2\n");
+        let (simple, multiplicity) = simplify(&graph);
+
+        assert_eq!(simple.node_count(), 4);
+        assert_eq!(simple.edge_count(), 4);
+        for e in simple.edge_references() {
+            assert_eq!(simple.edges_connecting(e.source(), e.target()).count(), 1);
+        }
+        assert_eq!(multiplicity[&(1, 2)], 3);
+        assert_eq!(multiplicity[&(3, 4)], 1);
+        assert_eq!(multiplicity.values().sum::<usize>(), graph.edge_count());
+    }
+
+    #[test]
+    fn test_line_graph() {
+        let triangle = crate::input::from_str("0,1\n1,2\n2,0\n");
+        let lg = line_graph(&triangle);
+        assert_eq!(lg.node_count(), 3);
+        assert_eq!(lg.edge_count(), 3);
+        assert_eq!(degree_sequence(&lg), vec![2, 2, 2]);
+
+        // path with 3 edges: 0 - 1 - 2 - 3
+        let path = crate::input::from_str("0,1\n1,2\n2,3\n");
+        let lg = line_graph(&path);
+        assert_eq!(lg.node_count(), 3);
+        assert_eq!(lg.edge_count(), 2);
+        assert_eq!(degree_sequence(&lg), vec![2, 1, 1]);
+        assert_eq!(lg[lg.neighbors(1.into()).next().unwrap()], 0);
+
+        // parallel edges are adjacent once
+        let bond = crate::input::from_str("0,1\n0,1\n0,1\n");
+        let lg = line_graph(&bond);
+        assert_eq!(lg.edge_count(), 3);
+    }
+
+    #[test]
+    fn test_edge_label_in_btreeset() {
+        let labels: std::collections::BTreeSet<EdgeLabel> = [
+            EdgeLabel::Virtual,
+            EdgeLabel::Real,
+            EdgeLabel::Structure,
+            EdgeLabel::Real,
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            labels.into_iter().collect::<Vec<_>>(),
+            vec![EdgeLabel::Real, EdgeLabel::Virtual, EdgeLabel::Structure]
+        );
+    }
+
+    #[test]
+    fn test_degree_stats_envelope() {
+        let graph = crate::input::from_file("assets/envelope.in");
+
+        assert_eq!(degree_sequence(&graph), vec![4, 4, 4, 4, 2, 2, 2, 2]);
+        assert_eq!(min_degree(&graph), Some(2));
+        assert_eq!(max_degree(&graph), Some(4));
+    }
+
+    #[test]
+    fn test_degree_stats_empty() {
+        let graph = UnGraph::new_undirected();
+
+        assert!(degree_sequence(&graph).is_empty());
+        assert_eq!(min_degree(&graph), None);
+        assert_eq!(max_degree(&graph), None);
+    }
+
+    #[test]
+    fn test_edge_multiplicity() {
+        let mut graph = UnGraph::new_undirected();
+        for i in 0..3 {
+            graph.add_node(10 * i);
+        }
+        for _ in 0..3 {
+            graph.add_edge(0.into(), 1.into(), EdgeLabel::Real);
+        }
+        graph.add_edge(2.into(), 1.into(), EdgeLabel::Real);
+
+        assert_eq!(edge_multiplicity(&graph, 0, 10), 3);
+        assert_eq!(edge_multiplicity(&graph, 10, 0), 3);
+        assert_eq!(edge_multiplicity(&graph, 10, 20), 1);
+        assert_eq!(edge_multiplicity(&graph, 0, 20), 0);
+        assert_eq!(edge_multiplicity(&graph, 0, 5), 0);
+    }
+
+    #[test]
+    fn test_undirected_of_embedding() {
+        let graph = crate::input::from_file("assets/envelope.in");
+        let (planar, embedding) = crate::embedding::is_planar(&graph, false);
+        assert!(planar);
+
+        let undirected = undirected_of(&embedding);
+        assert_eq!(undirected.node_count(), graph.node_count());
+        assert_eq!(2 * undirected.edge_count(), embedding.edge_count());
+        assert_eq!(degree_sequence(&undirected), degree_sequence(&graph));
+    }
+}